@@ -0,0 +1,6 @@
+// コンソール版。コマンドプロンプトや PowerShell は実行の終了を待ち、
+// 出力と終了コードを受け取れる（通常版はリリースビルドでウィンドウアプリになる）
+
+fn main() {
+    std::process::exit(advanced_shortcut_lib::run_cli())
+}
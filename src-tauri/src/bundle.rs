@@ -0,0 +1,577 @@
+use crate::crud::{self, GroupInput, ShortcutInput};
+use crate::data_format::DataFormat;
+use crate::storage::DEFAULT_GROUP_ID;
+use crate::trash::{TrashDelta, TrashEntry, TrashedItem};
+use crate::{AppData, Group, Shortcut};
+use serde::{Deserialize, Serialize};
+
+// ========================================
+// Bundle Format
+// ========================================
+
+/// Identifies bundle files, so a data.json or an unrelated JSON file is not
+/// imported by mistake.
+pub const BUNDLE_FORMAT: &str = "advanced-shortcut-bundle";
+/// バンドル形式のバージョン。形を変えたら上げる
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// 別の環境へ持ち出すためのショートカットとグループ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub groups: Vec<Group>,
+    pub shortcuts: Vec<Shortcut>,
+}
+
+/// What to export. Selecting a group exports all of its shortcuts; the
+/// groups of selected shortcuts are always included. Exports everything
+/// when both lists are empty.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSelection {
+    #[serde(default)]
+    pub shortcut_ids: Vec<String>,
+    #[serde(default)]
+    pub group_ids: Vec<String>,
+}
+
+/// How imported groups are matched against existing groups of the same name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupMode {
+    /// Add the imported shortcuts to the existing group.
+    #[default]
+    Merge,
+    /// Replace the existing group's appearance and shortcuts with the
+    /// imported ones.
+    Replace,
+}
+
+/// What to do with an imported shortcut when its group already has a
+/// shortcut of the same name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictMode {
+    /// Keep the existing shortcut and drop the imported one.
+    #[default]
+    Skip,
+    /// Import it as "name (2)".
+    Rename,
+    /// Overwrite the existing shortcut (its id and position are kept).
+    Replace,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    #[serde(default)]
+    pub groups: GroupMode,
+    #[serde(default)]
+    pub on_conflict: ConflictMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Merged,
+    Replaced,
+    Skipped,
+    Renamed,
+}
+
+impl ConflictResolution {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConflictResolution::Merged => "merged",
+            ConflictResolution::Replaced => "replaced",
+            ConflictResolution::Skipped => "skipped",
+            ConflictResolution::Renamed => "renamed",
+        }
+    }
+}
+
+/// 同じ名前の項目が既にあった場合の記録
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportConflict {
+    /// `"group"` or `"shortcut"`.
+    pub kind: &'static str,
+    pub name: String,
+    /// For shortcuts, the group the conflict happened in.
+    pub group_name: Option<String>,
+    pub resolution: ConflictResolution,
+    /// The name the shortcut was imported under when renamed.
+    pub new_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub created_groups: usize,
+    pub created_shortcuts: usize,
+    pub replaced_shortcuts: usize,
+    pub skipped_shortcuts: usize,
+    /// Shortcuts removed from replaced groups.
+    pub removed_shortcuts: usize,
+    pub conflicts: Vec<ImportConflict>,
+}
+
+// ========================================
+// Export
+// ========================================
+
+pub fn export(data: &AppData, selection: &ExportSelection) -> Result<Bundle, String> {
+    for id in &selection.shortcut_ids {
+        if !data.shortcuts.iter().any(|s| &s.id == id) {
+            return Err(format!("Shortcut not found: {}", id));
+        }
+    }
+    for id in &selection.group_ids {
+        if !data.groups.iter().any(|g| &g.id == id) {
+            return Err(format!("Group not found: {}", id));
+        }
+    }
+
+    let everything = selection.shortcut_ids.is_empty() && selection.group_ids.is_empty();
+    let mut shortcuts: Vec<Shortcut> = data
+        .shortcuts
+        .iter()
+        .filter(|s| {
+            everything
+                || selection.shortcut_ids.contains(&s.id)
+                || selection.group_ids.contains(&s.group_id)
+        })
+        .cloned()
+        .collect();
+    let mut groups: Vec<Group> = data
+        .groups
+        .iter()
+        .filter(|g| {
+            everything
+                || selection.group_ids.contains(&g.id)
+                || shortcuts.iter().any(|s| s.group_id == g.id)
+        })
+        .cloned()
+        .collect();
+    groups.sort_by_key(|g| g.order);
+    shortcuts.sort_by_key(|s| s.order);
+
+    Ok(Bundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        groups,
+        shortcuts,
+    })
+}
+
+pub fn to_string(bundle: &Bundle, format: DataFormat) -> Result<String, String> {
+    format
+        .serialize(bundle)
+        .map_err(|e| format!("Failed to serialize bundle: {}", e))
+}
+
+pub fn parse(content: &str, format: DataFormat) -> Result<Bundle, String> {
+    let value = format
+        .parse(content)
+        .map_err(|e| format!("Failed to parse bundle: {}", e))?;
+    if value.get("format").and_then(|f| f.as_str()) != Some(BUNDLE_FORMAT) {
+        return Err("Not a shortcut bundle (missing \"format\")".to_string());
+    }
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version > BUNDLE_VERSION as u64 {
+        return Err(format!(
+            "Bundle was written by a newer version of the app (version {}, this version supports up to {})",
+            version, BUNDLE_VERSION
+        ));
+    }
+    serde_json::from_value(value).map_err(|e| format!("Failed to parse bundle: {}", e))
+}
+
+// ========================================
+// Import
+// ========================================
+
+/// バンドルの内容を `data` に取り込む。id はすべて振り直す。
+/// グループの置き換えで消えるショートカットは `trash` に入れる
+pub fn import(
+    data: &mut AppData,
+    mut bundle: Bundle,
+    options: &ImportOptions,
+    trash: &mut TrashDelta,
+) -> Result<ImportReport, String> {
+    let mut report = ImportReport::default();
+    bundle.groups.sort_by_key(|g| g.order);
+    bundle.shortcuts.sort_by_key(|s| s.order);
+
+    // bundle group id → id in `data`
+    let mut group_ids: Vec<(String, String)> = Vec::new();
+    for group in &bundle.groups {
+        let existing = data
+            .groups
+            .iter()
+            .find(|g| {
+                (group.id == DEFAULT_GROUP_ID && g.id == DEFAULT_GROUP_ID) || g.name == group.name
+            })
+            .map(|g| g.id.clone());
+
+        let target = match existing {
+            Some(id) => {
+                let resolution = match options.groups {
+                    GroupMode::Merge => ConflictResolution::Merged,
+                    GroupMode::Replace => {
+                        for shortcut in replace_group(data, &id, group)? {
+                            report.removed_shortcuts += 1;
+                            let item = TrashedItem::Shortcut { shortcut };
+                            trash.added.push(TrashEntry::new(item));
+                        }
+                        ConflictResolution::Replaced
+                    }
+                };
+                report.conflicts.push(ImportConflict {
+                    kind: "group",
+                    name: group.name.clone(),
+                    group_name: None,
+                    resolution,
+                    new_name: None,
+                });
+                id
+            }
+            None => {
+                report.created_groups += 1;
+                crud::create_group(data, group_input(group))
+            }
+        };
+        group_ids.push((group.id.clone(), target));
+    }
+
+    for shortcut in bundle.shortcuts {
+        // 手で編集されたバンドルでグループが見つからなければデフォルトへ
+        let group_id = group_ids
+            .iter()
+            .find(|(from, _)| *from == shortcut.group_id)
+            .map(|(_, to)| to.clone())
+            .unwrap_or_else(|| DEFAULT_GROUP_ID.to_string());
+        let group_name = data
+            .groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .map(|g| g.name.clone());
+
+        let mut input = ShortcutInput {
+            name: shortcut.name,
+            icon: shortcut.icon,
+            group_id: group_id.clone(),
+            actions: shortcut.actions,
+            on_error: shortcut.on_error,
+            // 取り込んだショートカットは、リンクから実行する前に必ず確認する
+            link_runnable: false,
+        };
+
+        let Some(existing) = find_by_name(data, &group_id, &input.name) else {
+            crud::create_shortcut(data, input)?;
+            report.created_shortcuts += 1;
+            continue;
+        };
+
+        let mut conflict = ImportConflict {
+            kind: "shortcut",
+            name: input.name.clone(),
+            group_name,
+            resolution: ConflictResolution::Skipped,
+            new_name: None,
+        };
+        match options.on_conflict {
+            ConflictMode::Skip => report.skipped_shortcuts += 1,
+            ConflictMode::Rename => {
+                let new_name = unique_name(data, &group_id, &input.name);
+                input.name = new_name.clone();
+                crud::create_shortcut(data, input)?;
+                report.created_shortcuts += 1;
+                conflict.resolution = ConflictResolution::Renamed;
+                conflict.new_name = Some(new_name);
+            }
+            ConflictMode::Replace => {
+                crud::update_shortcut(data, &existing, input)?;
+                report.replaced_shortcuts += 1;
+                conflict.resolution = ConflictResolution::Replaced;
+            }
+        }
+        report.conflicts.push(conflict);
+    }
+
+    Ok(report)
+}
+
+fn group_input(group: &Group) -> GroupInput {
+    GroupInput {
+        name: group.name.clone(),
+        color: group.color.clone(),
+        icon: group.icon.clone(),
+        is_expanded: group.is_expanded,
+    }
+}
+
+/// Take over the imported group's appearance and remove the existing
+/// shortcuts. Returns the shortcuts removed from the group.
+fn replace_group(data: &mut AppData, id: &str, group: &Group) -> Result<Vec<Shortcut>, String> {
+    let mut input = group_input(group);
+    if id == DEFAULT_GROUP_ID {
+        // デフォルトグループは名前で照合していないので名前は変えない
+        if let Some(existing) = data.groups.iter().find(|g| g.id == id) {
+            input.name = existing.name.clone();
+        }
+    }
+    crud::update_group(data, id, input)?;
+
+    let (removed, kept) = std::mem::take(&mut data.shortcuts)
+        .into_iter()
+        .partition(|s| s.group_id == id);
+    data.shortcuts = kept;
+    Ok(removed)
+}
+
+fn find_by_name(data: &AppData, group_id: &str, name: &str) -> Option<String> {
+    data.shortcuts
+        .iter()
+        .find(|s| s.group_id == group_id && s.name == name)
+        .map(|s| s.id.clone())
+}
+
+fn unique_name(data: &AppData, group_id: &str, name: &str) -> String {
+    (2..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| find_by_name(data, group_id, candidate).is_none())
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{app_data, group, shortcut};
+
+    /// デフォルトグループ（名前は違う）、名前の同じ "Work"、新しい "Games" と、
+    /// グループの見つからないショートカット
+    fn sample_bundle() -> Bundle {
+        Bundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at: "2024-01-01T00:00:00.000Z".to_string(),
+            groups: vec![
+                Group {
+                    color: "#f97316".to_string(),
+                    ..group(DEFAULT_GROUP_ID, "Default", 0)
+                },
+                Group {
+                    color: "#f97316".to_string(),
+                    ..group("bundle-work", "Work", 1)
+                },
+                group("bundle-games", "Games", 2),
+            ],
+            shortcuts: vec![
+                Shortcut {
+                    link_runnable: true,
+                    ..shortcut("b1", "Backup", DEFAULT_GROUP_ID)
+                },
+                Shortcut {
+                    link_runnable: true,
+                    ..shortcut("b2", "Morning", "bundle-work")
+                },
+                shortcut("b3", "New", "bundle-work"),
+                shortcut("b4", "Play", "bundle-games"),
+                shortcut("b5", "Orphan", "bundle-missing"),
+            ],
+        }
+    }
+
+    fn sample_data() -> AppData {
+        app_data(
+            vec![
+                group(DEFAULT_GROUP_ID, "デフォルト", 0),
+                group("work", "Work", 1),
+            ],
+            vec![
+                shortcut("d1", "Backup", DEFAULT_GROUP_ID),
+                shortcut("w1", "Morning", "work"),
+                shortcut("w2", "Evening", "work"),
+            ],
+        )
+    }
+
+    fn import_sample(
+        groups: GroupMode,
+        on_conflict: ConflictMode,
+    ) -> (AppData, ImportReport, TrashDelta) {
+        let mut data = sample_data();
+        let mut trash = TrashDelta::default();
+        let options = ImportOptions {
+            groups,
+            on_conflict,
+        };
+        let report = import(&mut data, sample_bundle(), &options, &mut trash).unwrap();
+        (data, report, trash)
+    }
+
+    fn names_in(data: &AppData, group_id: &str) -> Vec<String> {
+        let mut shortcuts: Vec<&Shortcut> = data
+            .shortcuts
+            .iter()
+            .filter(|s| s.group_id == group_id)
+            .collect();
+        shortcuts.sort_by_key(|s| s.order);
+        shortcuts.iter().map(|s| s.name.clone()).collect()
+    }
+
+    fn group_id_named(data: &AppData, name: &str) -> String {
+        data.groups
+            .iter()
+            .find(|g| g.name == name)
+            .map(|g| g.id.clone())
+            .unwrap()
+    }
+
+    fn resolutions(report: &ImportReport) -> Vec<(&'static str, String, ConflictResolution)> {
+        report
+            .conflicts
+            .iter()
+            .map(|c| (c.kind, c.name.clone(), c.resolution))
+            .collect()
+    }
+
+    #[test]
+    fn merge_and_skip() {
+        let (data, report, trash) = import_sample(GroupMode::Merge, ConflictMode::Skip);
+
+        // デフォルトグループは名前ではなく id で、ほかは名前で照合する
+        assert_eq!(data.groups.len(), 3);
+        assert_eq!(report.created_groups, 1);
+        assert_eq!(data.groups[0].name, "デフォルト");
+        assert_eq!(data.groups[1].color, "#22d3ee");
+        let games = group_id_named(&data, "Games");
+        assert_ne!(games, "bundle-games");
+
+        assert_eq!(names_in(&data, DEFAULT_GROUP_ID), ["Backup", "Orphan"]);
+        assert_eq!(names_in(&data, "work"), ["Morning", "Evening", "New"]);
+        assert_eq!(names_in(&data, &games), ["Play"]);
+        assert_eq!((report.created_shortcuts, report.skipped_shortcuts), (3, 2));
+        assert_eq!(
+            resolutions(&report),
+            [
+                ("group", "Default".to_string(), ConflictResolution::Merged),
+                ("group", "Work".to_string(), ConflictResolution::Merged),
+                (
+                    "shortcut",
+                    "Backup".to_string(),
+                    ConflictResolution::Skipped
+                ),
+                (
+                    "shortcut",
+                    "Morning".to_string(),
+                    ConflictResolution::Skipped
+                ),
+            ]
+        );
+        assert!(trash.added.is_empty());
+    }
+
+    #[test]
+    fn imported_shortcuts_get_new_ids_and_no_link_permission() {
+        let (data, _, _) = import_sample(GroupMode::Merge, ConflictMode::Rename);
+
+        let imported: Vec<&Shortcut> = data
+            .shortcuts
+            .iter()
+            .filter(|s| !["d1", "w1", "w2"].contains(&s.id.as_str()))
+            .collect();
+        assert_eq!(imported.len(), 5);
+        for shortcut in imported {
+            assert!(
+                !["b1", "b2", "b3", "b4", "b5"].contains(&shortcut.id.as_str()),
+                "kept id {}",
+                shortcut.id
+            );
+            assert!(
+                !shortcut.link_runnable,
+                "{} may run from links",
+                shortcut.name
+            );
+        }
+    }
+
+    #[test]
+    fn rename_conflicts() {
+        let (data, report, _) = import_sample(GroupMode::Merge, ConflictMode::Rename);
+
+        assert_eq!(
+            names_in(&data, DEFAULT_GROUP_ID),
+            ["Backup", "Backup (2)", "Orphan"]
+        );
+        assert_eq!(
+            names_in(&data, "work"),
+            ["Morning", "Evening", "Morning (2)", "New"]
+        );
+        assert_eq!(report.created_shortcuts, 5);
+        let renamed: Vec<_> = report
+            .conflicts
+            .iter()
+            .filter_map(|c| c.new_name.as_deref())
+            .collect();
+        assert_eq!(renamed, ["Backup (2)", "Morning (2)"]);
+    }
+
+    #[test]
+    fn replace_conflicts_keep_the_existing_id() {
+        let mut data = sample_data();
+        data.shortcuts[0].link_runnable = true;
+        let mut trash = TrashDelta::default();
+        let options = ImportOptions {
+            groups: GroupMode::Merge,
+            on_conflict: ConflictMode::Replace,
+        };
+        let report = import(&mut data, sample_bundle(), &options, &mut trash).unwrap();
+
+        assert_eq!(report.replaced_shortcuts, 2);
+        assert_eq!(names_in(&data, "work"), ["Morning", "Evening", "New"]);
+        let backup = data.shortcuts.iter().find(|s| s.name == "Backup").unwrap();
+        assert_eq!(backup.id, "d1");
+        assert!(!backup.link_runnable);
+        assert!(trash.added.is_empty());
+    }
+
+    #[test]
+    fn replace_groups_moves_their_shortcuts_to_the_trash() {
+        let (data, report, trash) = import_sample(GroupMode::Replace, ConflictMode::Skip);
+
+        // 見た目は取り込んだものになるが、デフォルトグループの名前は変えない
+        assert_eq!(data.groups[0].name, "デフォルト");
+        assert_eq!(data.groups[0].color, "#f97316");
+        assert_eq!(data.groups[1].color, "#f97316");
+
+        assert_eq!(names_in(&data, DEFAULT_GROUP_ID), ["Backup", "Orphan"]);
+        assert_eq!(names_in(&data, "work"), ["Morning", "New"]);
+        assert_eq!(report.removed_shortcuts, 3);
+        assert_eq!(report.skipped_shortcuts, 0);
+        assert_eq!(report.created_shortcuts, 5);
+
+        let mut trashed: Vec<&str> = trash
+            .added
+            .iter()
+            .map(|entry| match &entry.item {
+                TrashedItem::Shortcut { shortcut } => shortcut.id.as_str(),
+                TrashedItem::Group { group, .. } => group.id.as_str(),
+            })
+            .collect();
+        trashed.sort();
+        assert_eq!(trashed, ["d1", "w1", "w2"]);
+        assert_eq!(
+            resolutions(&report)[..2],
+            [
+                ("group", "Default".to_string(), ConflictResolution::Replaced),
+                ("group", "Work".to_string(), ConflictResolution::Replaced),
+            ]
+        );
+    }
+}
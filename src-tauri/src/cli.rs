@@ -0,0 +1,1568 @@
+use crate::bundle::{ExportSelection, ImportOptions, ImportReport};
+use crate::data_format::DataFormat;
+use crate::executor::{self, ExecutionRuntime, RunPlan, RunRegistry, RunReport, RunStatus};
+use crate::history::RunSource;
+use crate::instance;
+use crate::link;
+use crate::storage::{self, load_app_data};
+use crate::validation::{self, Severity, ValidationReport};
+use crate::{
+    export_bundle_to_file, import_bundle_data, import_share_code_data, preview_import, read_bundle,
+    record_history, share_code, Action, ActionStep, AppData, ErrorPolicy, Group, Shortcut,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+// ========================================
+// Exit Codes
+// ========================================
+
+/// CLI終了コード: 実行できなかった（データ読み込み失敗・ショートカット不明など）
+pub const EXIT_ERROR: i32 = 1;
+/// CLI終了コード: 実行はしたが失敗したアクションがある
+pub const EXIT_ACTIONS_FAILED: i32 = 2;
+/// CLI終了コード: エラーポリシーにより途中で中断された
+pub const EXIT_ABORTED: i32 = 3;
+/// CLI終了コード: `validate` でエラーが見つかった
+pub const EXIT_INVALID_DATA: i32 = 4;
+/// CLI終了コード: リンクからの実行が確認で拒否された
+pub const EXIT_NOT_CONFIRMED: i32 = 5;
+/// CLI終了コード: コマンドや引数が正しくない（sysexits の EX_USAGE）
+pub const EXIT_USAGE: i32 = 64;
+/// CLI終了コード: Ctrl+C でキャンセルされた
+pub const EXIT_CANCELLED: i32 = 130;
+
+/// Exit codes grow with severity, so the worst of several runs is the
+/// largest code.
+fn exit_code_for(status: RunStatus) -> i32 {
+    match status {
+        RunStatus::Succeeded => 0,
+        RunStatus::Failed => EXIT_ACTIONS_FAILED,
+        RunStatus::Aborted => EXIT_ABORTED,
+        RunStatus::Cancelled => EXIT_CANCELLED,
+    }
+}
+
+const HELP: &str = "\
+Usage: advanced-shortcut [OPTIONS] [COMMAND]
+       advanced-shortcut [OPTIONS] advanced-shortcut://run/<shortcut>[?<name>=<value>&...]
+
+Without a command the app window opens. On Windows, scripts should call
+advanced-shortcut-cli instead: it is a console program, so shells wait for
+it and see its exit code.
+
+Commands:
+  list [shortcuts|groups] [--json]      List shortcuts (default) or groups
+  show <shortcut> [--json]              Show the actions of a shortcut
+  run <shortcut> [--dry-run] [--json|--ndjson]
+                                        Run a shortcut
+  run --name <name> [--dry-run] [--json|--ndjson]
+                                        Run a shortcut by its exact name
+  run-group <group> [--dry-run] [--json|--ndjson]
+                                        Run every shortcut of a group in order
+  validate [<file>] [--json]            Check the data file (or another data file)
+  export <file> [--group <id>]... [--shortcut <id>]...
+                                        Export shortcuts and groups as a bundle
+  import <file> [--groups merge|replace] [--on-conflict skip|rename|replace] [--dry-run]
+                                        Import a bundle
+  import --code <code> [--group <id>] [--dry-run]
+                                        Import a shortcut from a share code
+  help                                  Show this help
+  version                               Show the version
+
+<shortcut> and <group> are an id or a name. A name may be given partly
+(\"morn set\" finds \"Morning setup\") as long as only one matches.
+Shortcut names may be qualified with their group (\"Work/Morning setup\").
+--name only accepts whole names, so it keeps working after the shortcut is
+re-imported with a new id but never picks a different shortcut.
+
+If the app is already running with the same data folder and profile, run
+and run-group hand the shortcut over to it and wait for the result, so the
+run shows up in the app and can be cancelled there. Otherwise the shortcut
+runs in this process.
+
+Links (advanced-shortcut://run/<shortcut>) open this app from browsers and
+documents. <shortcut> is an id or a whole name (\"run/Work/Morning%20setup\"
+for a group-qualified one). Query parameters fill in ${<name>} in launch
+arguments and URLs as they are; every parameter must be used by the
+shortcut and may not be named like an environment variable. Unless the
+shortcut allows running from links, you are asked before it runs.
+
+--json prints the results of every action, their timings and the final
+status as one JSON document when the run has finished. --ndjson prints every
+progress event (run_started, action_started, action_finished, run_finished)
+as one line of JSON while the run progresses. With --dry-run both print the
+plan instead.
+
+Options:
+  --data-dir <dir>      Use another data folder
+  --portable            Keep data in the \"data\" folder next to the executable
+  --profile <name>      Use another profile for this run only
+  -h, --help            Show this help
+  -V, --version         Show the version
+
+Exit codes:
+  0    Success
+  1    Error (data could not be loaded, shortcut not found, ...)
+  2    Some actions failed (or would fail, with --dry-run)
+  3    The run was aborted by an error policy
+  4    validate found errors
+  5    Running from a link was not confirmed
+  64   Invalid command or arguments
+  130  Cancelled with Ctrl+C
+";
+
+// ========================================
+// Commands
+// ========================================
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Help,
+    Version,
+    /// `list [shortcuts|groups] [--json]`
+    List {
+        groups: bool,
+        json: bool,
+    },
+    /// `show <shortcut> [--json]`, or `show --name <name>`
+    Show {
+        query: ShortcutQuery,
+        json: bool,
+    },
+    /// `run <shortcut> [--dry-run]`, `run --name <name>`, or the flags
+    /// `--execute-shortcut <id>` / `--name <name>` with `[--dry-run]`
+    Run {
+        query: ShortcutQuery,
+        dry_run: bool,
+        output: Output,
+    },
+    /// `run-group <group> [--dry-run]`
+    RunGroup {
+        query: String,
+        dry_run: bool,
+        output: Output,
+    },
+    /// `validate [<file>] [--json]`
+    Validate {
+        path: Option<PathBuf>,
+        json: bool,
+    },
+    /// `export <file>`, or `--export-bundle <file>`
+    Export {
+        path: PathBuf,
+        selection: ExportSelection,
+    },
+    /// `import <file>`, or `--import-bundle <file>`
+    Import {
+        path: PathBuf,
+        options: ImportOptions,
+        dry_run: bool,
+    },
+    /// `import --code <code>`, or `--import-code <code>`
+    ImportCode {
+        code: String,
+        group_id: Option<String>,
+        dry_run: bool,
+    },
+    /// `advanced-shortcut://run/<shortcut>?<name>=<value>`, as passed by the
+    /// browser. The link is checked when the command runs.
+    OpenLink {
+        link: String,
+    },
+}
+
+/// How a command names the shortcut it works on.
+#[derive(Debug, Clone)]
+pub enum ShortcutQuery {
+    /// `--execute-shortcut` only takes ids, so a desktop shortcut whose
+    /// shortcut was deleted never runs another one by name.
+    Id(String),
+    /// `--name`: an exact name, optionally qualified with its group
+    /// (`Work/Morning setup`). Case is ignored if nothing matches exactly.
+    Name(String),
+    /// An id, an exact name, or a unique partial name.
+    IdOrName(String),
+    /// Links: an id or an exact name, never a partial one, so a link does not
+    /// start a different shortcut after renames.
+    IdOrExactName(String),
+}
+
+/// `run` / `run-group` の出力形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Output {
+    /// One summary line per run. Failed actions are reported on stderr.
+    #[default]
+    Text,
+    /// `--json`: one JSON document once everything has finished.
+    Json,
+    /// `--ndjson`: every progress event as one line of JSON while running.
+    Ndjson,
+}
+
+impl Output {
+    fn from_flags(json: bool, ndjson: bool) -> Result<Self, String> {
+        match (json, ndjson) {
+            (false, false) => Ok(Output::Text),
+            (true, false) => Ok(Output::Json),
+            (false, true) => Ok(Output::Ndjson),
+            (true, true) => Err("--json and --ndjson cannot be combined".to_string()),
+        }
+    }
+}
+
+/// Options for the subcommand, in the order they appear in [`HELP`].
+fn allowed_options(command: &str) -> Option<&'static [&'static str]> {
+    Some(match command {
+        "list" | "validate" => &["--json"],
+        "show" => &["--name", "--json"],
+        "run" => &["--name", "--dry-run", "--json", "--ndjson"],
+        "run-group" => &["--dry-run", "--json", "--ndjson"],
+        "export" => &["--group", "--shortcut"],
+        "import" => &[
+            "--groups",
+            "--on-conflict",
+            "--code",
+            "--group",
+            "--dry-run",
+        ],
+        "help" | "version" => &[],
+        _ => return None,
+    })
+}
+
+// ========================================
+// Parsing
+// ========================================
+
+struct Args(std::vec::IntoIter<String>);
+
+impl Args {
+    fn next(&mut self) -> Option<String> {
+        self.0.next()
+    }
+
+    fn value(&mut self, flag: &str) -> Result<String, String> {
+        self.0
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", flag))
+    }
+}
+
+/// コマンドライン引数（実行ファイル名を除く）を解釈する。
+/// `None` ならコマンドは無く、GUI を起動する。
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Command>, String> {
+    let args = strip_global_options(args)?;
+    match args.first() {
+        Some(arg) if link::is_link(arg) => match args.as_slice() {
+            [link] => Ok(Some(Command::OpenLink { link: link.clone() })),
+            [_, extra, ..] => Err(format!("Unexpected argument after link: {}", extra)),
+            [] => unreachable!(),
+        },
+        Some(command) if !command.starts_with('-') => {
+            let command = command.clone();
+            parse_subcommand(&command, args.into_iter().skip(1).collect()).map(Some)
+        }
+        _ => parse_flags(args),
+    }
+}
+
+/// `--data-dir`, `--portable` and `--profile` are read by `data_dir` and
+/// `profiles` before any command runs.
+fn strip_global_options(args: impl IntoIterator<Item = String>) -> Result<Vec<String>, String> {
+    let mut args = Args(args.into_iter().collect::<Vec<_>>().into_iter());
+    let mut rest = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--data-dir" | "--profile" => {
+                args.value(&arg)?;
+            }
+            "--portable" => {}
+            _ => rest.push(arg),
+        }
+    }
+    Ok(rest)
+}
+
+fn parse_subcommand(command: &str, args: Vec<String>) -> Result<Command, String> {
+    let allowed =
+        allowed_options(command).ok_or_else(|| format!("Unknown command: {}", command))?;
+
+    let mut positional = Vec::new();
+    let mut json = false;
+    let mut ndjson = false;
+    let mut dry_run = false;
+    let mut code = None;
+    let mut name = None;
+    let mut selection = ExportSelection::default();
+    let mut options = ImportOptions::default();
+    let mut import_option = None;
+
+    let mut args = Args(args.into_iter());
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            return Ok(Command::Help);
+        }
+        if arg.starts_with('-') && arg.len() > 1 && !allowed.contains(&arg.as_str()) {
+            return Err(format!("Unknown option for {}: {}", command, arg));
+        }
+        match arg.as_str() {
+            "--json" => json = true,
+            "--ndjson" => ndjson = true,
+            "--dry-run" => dry_run = true,
+            "--code" => code = Some(args.value(&arg)?),
+            "--name" => name = Some(args.value(&arg)?),
+            "--shortcut" => selection.shortcut_ids.push(args.value(&arg)?),
+            "--group" => selection.group_ids.push(args.value(&arg)?),
+            "--groups" => {
+                options.groups = parse_choice(&arg, args.value(&arg)?)?;
+                import_option = Some(arg);
+            }
+            "--on-conflict" => {
+                options.on_conflict = parse_choice(&arg, args.value(&arg)?)?;
+                import_option = Some(arg);
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let command = match command {
+        "help" => Command::Help,
+        "version" => Command::Version,
+        "list" => {
+            let groups = match positional.next().as_deref() {
+                None | Some("shortcuts") => false,
+                Some("groups") => true,
+                Some(other) => return Err(format!("Cannot list \"{}\"", other)),
+            };
+            Command::List { groups, json }
+        }
+        "show" => Command::Show {
+            query: shortcut_query(name, &mut positional)?,
+            json,
+        },
+        "run" => Command::Run {
+            query: shortcut_query(name, &mut positional)?,
+            dry_run,
+            output: Output::from_flags(json, ndjson)?,
+        },
+        "run-group" => Command::RunGroup {
+            query: required(&mut positional, "group")?,
+            dry_run,
+            output: Output::from_flags(json, ndjson)?,
+        },
+        "validate" => Command::Validate {
+            path: positional.next().map(PathBuf::from),
+            json,
+        },
+        "export" => Command::Export {
+            path: PathBuf::from(required(&mut positional, "file")?),
+            selection,
+        },
+        "import" => match code {
+            Some(code) => Command::ImportCode {
+                code,
+                group_id: code_group("--code", selection.group_ids, import_option)?,
+                dry_run,
+            },
+            None if !selection.group_ids.is_empty() => {
+                return Err("--group is only used with --code".to_string())
+            }
+            None => Command::Import {
+                path: PathBuf::from(required(&mut positional, "file")?),
+                options,
+                dry_run,
+            },
+        },
+        _ => unreachable!("checked by allowed_options"),
+    };
+
+    match positional.next() {
+        Some(extra) => Err(format!("Unexpected argument: {}", extra)),
+        None => Ok(command),
+    }
+}
+
+/// `--name <name>` or a positional id/name (not both: a leftover positional
+/// argument is rejected by the caller).
+fn shortcut_query(
+    name: Option<String>,
+    positional: &mut impl Iterator<Item = String>,
+) -> Result<ShortcutQuery, String> {
+    match name {
+        Some(name) => Ok(ShortcutQuery::Name(name)),
+        None => required(positional, "shortcut").map(ShortcutQuery::IdOrName),
+    }
+}
+
+fn required(positional: &mut impl Iterator<Item = String>, what: &str) -> Result<String, String> {
+    positional
+        .next()
+        .ok_or_else(|| format!("Missing <{}>", what))
+}
+
+/// The flags from before subcommands existed (desktop shortcuts created by
+/// older versions still pass them). Unknown flags are ignored and open the
+/// GUI, as they always did.
+fn parse_flags(args: Vec<String>) -> Result<Option<Command>, String> {
+    let mut commands = Vec::new();
+    let mut dry_run = false;
+    let mut json = false;
+    let mut ndjson = false;
+    let mut selection = ExportSelection::default();
+    let mut options = ImportOptions::default();
+    let mut import_option = None;
+
+    let mut args = Args(args.into_iter());
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(Some(Command::Help)),
+            "--version" | "-V" => return Ok(Some(Command::Version)),
+            "--execute-shortcut" => commands.push((arg.clone(), args.value(&arg)?)),
+            "--name" => commands.push((arg.clone(), args.value(&arg)?)),
+            "--export-bundle" => commands.push((arg.clone(), args.value(&arg)?)),
+            "--import-bundle" => commands.push((arg.clone(), args.value(&arg)?)),
+            "--import-code" => commands.push((arg.clone(), args.value(&arg)?)),
+            "--shortcut" => selection.shortcut_ids.push(args.value(&arg)?),
+            "--group" => selection.group_ids.push(args.value(&arg)?),
+            "--groups" => {
+                options.groups = parse_choice(&arg, args.value(&arg)?)?;
+                import_option = Some(arg);
+            }
+            "--on-conflict" => {
+                options.on_conflict = parse_choice(&arg, args.value(&arg)?)?;
+                import_option = Some(arg);
+            }
+            "--dry-run" => dry_run = true,
+            "--json" => json = true,
+            "--ndjson" => ndjson = true,
+            _ => {}
+        }
+    }
+
+    if commands.len() > 1 {
+        return Err(format!(
+            "{} cannot be combined",
+            commands
+                .iter()
+                .map(|(flag, _)| flag.as_str())
+                .collect::<Vec<_>>()
+                .join(" and ")
+        ));
+    }
+    let Some((flag, value)) = commands.pop() else {
+        return Ok(None);
+    };
+
+    Ok(Some(match flag.as_str() {
+        "--execute-shortcut" => Command::Run {
+            query: ShortcutQuery::Id(value),
+            dry_run,
+            output: Output::from_flags(json, ndjson)?,
+        },
+        "--name" => Command::Run {
+            query: ShortcutQuery::Name(value),
+            dry_run,
+            output: Output::from_flags(json, ndjson)?,
+        },
+        "--export-bundle" => Command::Export {
+            path: PathBuf::from(value),
+            selection,
+        },
+        "--import-bundle" => Command::Import {
+            path: PathBuf::from(value),
+            options,
+            dry_run,
+        },
+        _ => Command::ImportCode {
+            code: value,
+            group_id: code_group(&flag, selection.group_ids, import_option)?,
+            dry_run,
+        },
+    }))
+}
+
+/// The destination group of a share code import. The bundle import options
+/// do not apply to a single shortcut, so they are rejected instead of ignored.
+fn code_group(
+    code_flag: &str,
+    mut group_ids: Vec<String>,
+    import_option: Option<String>,
+) -> Result<Option<String>, String> {
+    if let Some(option) = import_option {
+        return Err(format!("{} is not used with {}", option, code_flag));
+    }
+    if group_ids.len() > 1 {
+        return Err(format!("--group can only be given once with {}", code_flag));
+    }
+    Ok(group_ids.pop())
+}
+
+/// Parse a CLI value with the same snake_case names the commands accept.
+fn parse_choice<T: serde::de::DeserializeOwned>(flag: &str, value: String) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(value.clone()))
+        .map_err(|_| format!("Invalid value for {}: {}", flag, value))
+}
+
+// ========================================
+// Console (Windows)
+// ========================================
+
+/// 起動元のコマンドプロンプトや PowerShell のコンソールに標準出力・標準エラーをつなぐ。
+///
+/// Release builds use the windows subsystem, so they start without a console
+/// and anything printed is lost. Those shells do not wait for such a process,
+/// though, so scripts that need the exit code use `advanced-shortcut-cli`.
+/// Redirected output keeps going to its file or pipe.
+#[cfg(windows)]
+pub fn attach_parent_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // ダブルクリックやリンクから起動された場合は親にコンソールが無く、失敗してよい
+    unsafe {
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+// ========================================
+// Running Commands
+// ========================================
+
+/// コマンドを実行し、プロセスの終了コードを返す
+pub fn run(command: Command) -> i32 {
+    let result = match command {
+        Command::Help => {
+            print!("{}", HELP);
+            Ok(0)
+        }
+        Command::Version => {
+            println!("advanced-shortcut {}", env!("CARGO_PKG_VERSION"));
+            Ok(0)
+        }
+        Command::List { groups, json } => list(groups, json),
+        Command::Show { query, json } => show(&query, json),
+        Command::Run {
+            query,
+            dry_run,
+            output,
+        } => run_shortcut(&query, dry_run, output),
+        Command::RunGroup {
+            query,
+            dry_run,
+            output,
+        } => run_group(&query, dry_run, output),
+        Command::Validate { path, json } => validate(path, json),
+        Command::Export { path, selection } => export(&path, &selection),
+        Command::Import {
+            path,
+            options,
+            dry_run,
+        } => import(&path, &options, dry_run),
+        Command::ImportCode {
+            code,
+            group_id,
+            dry_run,
+        } => import_code(&code, group_id, dry_run),
+        Command::OpenLink { link } => Ok(open_link(&link)),
+    };
+
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        EXIT_ERROR
+    })
+}
+
+fn list(groups: bool, json: bool) -> Result<i32, String> {
+    let data = load_app_data()?;
+    let sorted_groups = sorted_groups(&data);
+
+    if groups {
+        if json {
+            print_json(&sorted_groups)?;
+        } else {
+            let rows = sorted_groups
+                .iter()
+                .map(|group| {
+                    let count = data
+                        .shortcuts
+                        .iter()
+                        .filter(|s| s.group_id == group.id)
+                        .count();
+                    vec![group.id.clone(), group.name.clone(), count.to_string()]
+                })
+                .collect();
+            print_table(&["ID", "NAME", "SHORTCUTS"], rows);
+        }
+        return Ok(0);
+    }
+
+    // グループの並び順 → グループ内の並び順（グループの無いものは最後）
+    let mut shortcuts: Vec<&Shortcut> = data.shortcuts.iter().collect();
+    shortcuts.sort_by_key(|s| {
+        let group = sorted_groups.iter().position(|g| g.id == s.group_id);
+        (group.unwrap_or(usize::MAX), s.order)
+    });
+
+    if json {
+        print_json(&shortcuts)?;
+    } else {
+        let rows = shortcuts
+            .iter()
+            .map(|s| {
+                vec![
+                    s.id.clone(),
+                    s.name.clone(),
+                    group_name(&data, &s.group_id),
+                    s.actions.len().to_string(),
+                ]
+            })
+            .collect();
+        print_table(&["ID", "NAME", "GROUP", "ACTIONS"], rows);
+    }
+    Ok(0)
+}
+
+fn show(query: &ShortcutQuery, json: bool) -> Result<i32, String> {
+    let data = load_app_data()?;
+    let shortcut = find_shortcut(&data, query)?;
+
+    if json {
+        print_json(shortcut)?;
+        return Ok(0);
+    }
+
+    println!("{}", shortcut.name);
+    println!("  id:       {}", shortcut.id);
+    println!(
+        "  group:    {} ({})",
+        group_name(&data, &shortcut.group_id),
+        shortcut.group_id
+    );
+    println!("  on error: {}", describe_policy(&shortcut.on_error));
+    println!("  actions:");
+    for (index, step) in shortcut.actions.iter().enumerate() {
+        println!("    {}. {}", index + 1, describe_step(step));
+    }
+    Ok(0)
+}
+
+fn run_shortcut(query: &ShortcutQuery, dry_run: bool, output: Output) -> Result<i32, String> {
+    let data = load_app_data()?;
+    let shortcut = find_shortcut(&data, query)?;
+
+    if dry_run {
+        let plan = plan(shortcut, output)?;
+        if output == Output::Json {
+            print_json(&plan)?;
+        }
+        return Ok(if plan.ok { 0 } else { EXIT_ACTIONS_FAILED });
+    }
+
+    let registry = cancellable_registry();
+    let report = execute(
+        &registry,
+        shortcut,
+        &BTreeMap::new(),
+        RunSource::Cli,
+        output,
+    )?;
+    let exit_code = exit_code_for(report.status);
+    if output == Output::Json {
+        print_json(&RunOutput {
+            shortcut_name: &shortcut.name,
+            exit_code,
+            error: None,
+            report: Some(&report),
+        })?;
+    }
+    Ok(exit_code)
+}
+
+/// グループ内のショートカットを並び順に1つずつ実行する。
+/// 終了コードは最も悪い結果のもの。キャンセルされたら残りは実行しない。
+/// 実行できなかったショートカットはエラーを結果として記録し、次へ進む。
+fn run_group(query: &str, dry_run: bool, output: Output) -> Result<i32, String> {
+    let data = load_app_data()?;
+    let group = find_group(&data, query)?;
+
+    let mut shortcuts: Vec<&Shortcut> = data
+        .shortcuts
+        .iter()
+        .filter(|s| s.group_id == group.id)
+        .collect();
+    if shortcuts.is_empty() {
+        return Err(format!("Group \"{}\" has no shortcuts", group.name));
+    }
+    shortcuts.sort_by_key(|s| s.order);
+
+    if dry_run {
+        // 1つ目が失敗しそうでも、残りの計画も表示する
+        let plans = shortcuts
+            .into_iter()
+            .map(|shortcut| plan(shortcut, output))
+            .collect::<Result<Vec<RunPlan>, String>>()?;
+        let exit_code = if plans.iter().all(|plan| plan.ok) {
+            0
+        } else {
+            EXIT_ACTIONS_FAILED
+        };
+        if output == Output::Json {
+            print_json(&GroupOutput::new(group, exit_code, plans))?;
+        }
+        return Ok(exit_code);
+    }
+
+    let registry = cancellable_registry();
+    let mut reports = Vec::new();
+    for shortcut in shortcuts {
+        let result = execute(
+            &registry,
+            shortcut,
+            &BTreeMap::new(),
+            RunSource::Cli,
+            output,
+        );
+        if let Err(e) = &result {
+            eprintln!("Error: \"{}\": {}", shortcut.name, e);
+        }
+        let cancelled = matches!(&result, Ok(report) if report.status == RunStatus::Cancelled);
+        reports.push((shortcut, result));
+        if cancelled {
+            break;
+        }
+    }
+
+    let run_exit_code = |result: &Result<RunReport, String>| match result {
+        Ok(report) => exit_code_for(report.status),
+        Err(_) => EXIT_ERROR,
+    };
+    let exit_code = reports
+        .iter()
+        .map(|(_, result)| run_exit_code(result))
+        .max()
+        .unwrap_or(0);
+    if output == Output::Json {
+        let runs = reports
+            .iter()
+            .map(|(shortcut, result)| RunOutput {
+                shortcut_name: &shortcut.name,
+                exit_code: run_exit_code(result),
+                error: result.as_ref().err().map(String::as_str),
+                report: result.as_ref().ok(),
+            })
+            .collect();
+        print_json(&GroupOutput::new(group, exit_code, runs))?;
+    }
+    Ok(exit_code)
+}
+
+/// 指定したファイル（省略時は使用中のデータファイル）を検査する。
+/// 壊れたファイルを退避する `load_app_data` は使わず、読むだけにする。
+fn validate(path: Option<PathBuf>, json: bool) -> Result<i32, String> {
+    let explicit = path.is_some();
+    let path = path.unwrap_or_else(crate::get_data_path);
+
+    let data = match fs::read_to_string(&path) {
+        Ok(content) => {
+            let format = DataFormat::from_path(&path).unwrap_or_default();
+            match storage::parse_app_data(&content, format) {
+                Ok((data, _)) => data,
+                Err(e) => {
+                    eprintln!("Error: {} cannot be read: {}", path.display(), e);
+                    return Ok(EXIT_INVALID_DATA);
+                }
+            }
+        }
+        // まだ保存されていなければ初期データを検査する
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && !explicit => {
+            storage::default_app_data()
+        }
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    let report = validation::validate(&data);
+    if json {
+        print_json(&report)?;
+    } else {
+        print_validation_report(&report, &path);
+    }
+    Ok(if report.has_errors() {
+        EXIT_INVALID_DATA
+    } else {
+        0
+    })
+}
+
+fn export(path: &std::path::Path, selection: &ExportSelection) -> Result<i32, String> {
+    let summary = export_bundle_to_file(path, selection)?;
+    println!(
+        "Exported {} shortcuts and {} groups to {}",
+        summary.shortcut_count, summary.group_count, summary.path
+    );
+    Ok(0)
+}
+
+fn import(path: &std::path::Path, options: &ImportOptions, dry_run: bool) -> Result<i32, String> {
+    let bundle = read_bundle(path)?;
+    let report = if dry_run {
+        preview_import(bundle, options)?
+    } else {
+        import_bundle_data(bundle, options, None)?.1
+    };
+    print_import_report(&report);
+    if dry_run {
+        println!("(dry run: nothing was saved)");
+    }
+    Ok(0)
+}
+
+fn import_code(code: &str, group_id: Option<String>, dry_run: bool) -> Result<i32, String> {
+    if dry_run {
+        let shared = share_code::decode(code)?;
+        println!(
+            "Share code contains \"{}\" with {} actions",
+            shared.name,
+            shared.actions.len()
+        );
+        println!("(dry run: nothing was saved)");
+    } else {
+        let result = import_share_code_data(code, group_id, None)?;
+        let id = result.id.unwrap_or_default();
+        let shortcut = result.data.shortcuts.iter().find(|s| s.id == id);
+        println!(
+            "Imported \"{}\" ({})",
+            shortcut.map(|s| s.name.as_str()).unwrap_or_default(),
+            id
+        );
+    }
+    Ok(0)
+}
+
+/// `advanced-shortcut://run/...` のリンクから実行する。リンクはコンソールの無い
+/// ブラウザなどから開かれるので、実行できなかった理由はダイアログでも伝える
+fn open_link(link: &str) -> i32 {
+    let request = match link::parse(link) {
+        Ok(request) => request,
+        Err(e) => {
+            link::report_error(&e);
+            return EXIT_USAGE;
+        }
+    };
+    let shortcut = match load_app_data().and_then(|data| {
+        let query = ShortcutQuery::IdOrExactName(request.target.clone());
+        executor::apply_link_variables(find_shortcut(&data, &query)?, &request.variables)
+    }) {
+        Ok(shortcut) => shortcut,
+        Err(e) => {
+            link::report_error(&e);
+            return EXIT_ERROR;
+        }
+    };
+
+    // 許可されていないショートカットは、実行する前に必ず確認する。
+    // 起動中のアプリもこの場合は引き受けず、確認したこのプロセスで実行する
+    if !shortcut.link_runnable && !link::confirm(&shortcut.name, &request.variables) {
+        eprintln!("\"{}\" was not run", shortcut.name);
+        return EXIT_NOT_CONFIRMED;
+    }
+
+    let registry = cancellable_registry();
+    match execute(
+        &registry,
+        &shortcut,
+        &request.variables,
+        RunSource::Trigger,
+        Output::Text,
+    ) {
+        Ok(report) => {
+            if matches!(report.status, RunStatus::Failed | RunStatus::Aborted) {
+                link::report_error(&format!(
+                    "\"{}\": {}",
+                    shortcut.name,
+                    status_label(report.status)
+                ));
+            }
+            exit_code_for(report.status)
+        }
+        Err(e) => {
+            link::report_error(&e);
+            EXIT_ERROR
+        }
+    }
+}
+
+// ========================================
+// Execution
+// ========================================
+
+/// Ctrl+C で実行中のショートカットをキャンセルできるようにする
+fn cancellable_registry() -> RunRegistry {
+    let registry = RunRegistry::default();
+    let handler_registry = registry.clone();
+    if let Err(e) = ctrlc::set_handler(move || handler_registry.cancel_all()) {
+        eprintln!("Failed to install Ctrl+C handler: {}", e);
+    }
+    registry
+}
+
+/// `run --json` の出力（ショートカット1件分）
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RunOutput<'a> {
+    shortcut_name: &'a str,
+    exit_code: i32,
+    /// `run-group` で実行できなかった場合のエラー（`report` はない）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+    #[serde(flatten)]
+    report: Option<&'a RunReport>,
+}
+
+/// `run-group --json` の出力。`runs` は実行結果、`--dry-run` では実行計画
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GroupOutput<'a, T> {
+    group_id: &'a str,
+    group_name: &'a str,
+    exit_code: i32,
+    runs: Vec<T>,
+}
+
+impl<'a, T> GroupOutput<'a, T> {
+    fn new(group: &'a Group, exit_code: i32, runs: Vec<T>) -> Self {
+        Self {
+            group_id: &group.id,
+            group_name: &group.name,
+            exit_code,
+            runs,
+        }
+    }
+}
+
+/// アプリが起動していればそちらで実行し（進捗・キャンセル・履歴が画面にも
+/// 反映される）、起動していなければGUIと同じ実行エンジンでこのプロセスで実行する。
+/// リンクから実行する場合、`shortcut` には `variables` を埋め込み済み
+fn execute(
+    registry: &RunRegistry,
+    shortcut: &Shortcut,
+    variables: &BTreeMap<String, String>,
+    source: RunSource,
+    output: Output,
+) -> Result<RunReport, String> {
+    let run = registry.begin(&shortcut.id);
+    let runtime: &dyn ExecutionRuntime = match output {
+        Output::Ndjson => &executor::NdjsonRuntime,
+        Output::Text | Output::Json => &executor::CliRuntime,
+    };
+
+    let report = match instance::forward_run(&shortcut.id, variables, source, &run, runtime) {
+        Some(report) => report?,
+        None => {
+            let report = executor::execute_shortcut(shortcut, &run, runtime);
+            record_history(shortcut, &report, source);
+            report
+        }
+    };
+
+    if output == Output::Text {
+        println!(
+            "\"{}\": {} ({} actions, {}ms)",
+            shortcut.name,
+            status_label(report.status),
+            report.results.len(),
+            report.duration_ms
+        );
+    }
+    Ok(report)
+}
+
+/// `--dry-run`: 実行計画を作る（何も起動・終了しない）。
+/// テキストと NDJSON はその場で表示し、JSON は呼び出し元がまとめて表示する
+fn plan(shortcut: &Shortcut, output: Output) -> Result<RunPlan, String> {
+    let plan = executor::plan_shortcut(shortcut);
+    match output {
+        Output::Text => print_plan(&plan),
+        Output::Json => {}
+        Output::Ndjson => print_json_line(&plan)?,
+    }
+    Ok(plan)
+}
+
+fn print_plan(plan: &RunPlan) {
+    println!("Plan for \"{}\" ({})", plan.shortcut_name, plan.shortcut_id);
+    for action in &plan.actions {
+        println!("  {}. {}", action.index + 1, action.summary);
+        for warning in &action.warnings {
+            println!("     warning: {}", warning);
+        }
+        if let Some(error) = &action.error {
+            println!("     would fail: {}", error);
+        }
+    }
+}
+
+fn status_label(status: RunStatus) -> &'static str {
+    match status {
+        RunStatus::Succeeded => "succeeded",
+        RunStatus::Failed => "some actions failed",
+        RunStatus::Aborted => "aborted",
+        RunStatus::Cancelled => "cancelled",
+    }
+}
+
+// ========================================
+// Lookup
+// ========================================
+
+/// ショートカット名は「グループ名/名前」の形でグループを付けて指定してもよい
+const GROUP_SEPARATOR: char = '/';
+
+fn find_shortcut<'a>(data: &'a AppData, query: &ShortcutQuery) -> Result<&'a Shortcut, String> {
+    let candidates = || {
+        data.shortcuts
+            .iter()
+            .map(|s| Candidate {
+                item: s,
+                id: &s.id,
+                name: &s.name,
+                qualified: format!(
+                    "{}{}{}",
+                    group_name(data, &s.group_id),
+                    GROUP_SEPARATOR,
+                    s.name
+                ),
+            })
+            .collect()
+    };
+    let hint = format!(
+        "use the id or the group-qualified name (<group>{}<name>)",
+        GROUP_SEPARATOR
+    );
+
+    match query {
+        ShortcutQuery::Id(id) => data
+            .shortcuts
+            .iter()
+            .find(|s| &s.id == id)
+            .ok_or_else(|| format!("Shortcut not found: {}", id)),
+        ShortcutQuery::Name(name) => find_one(candidates(), name, Lookup::Name, "shortcut", &hint),
+        ShortcutQuery::IdOrName(query) => {
+            find_one(candidates(), query, Lookup::Any, "shortcut", &hint)
+        }
+        ShortcutQuery::IdOrExactName(query) => {
+            find_one(candidates(), query, Lookup::IdOrName, "shortcut", &hint)
+        }
+    }
+}
+
+fn find_group<'a>(data: &'a AppData, query: &str) -> Result<&'a Group, String> {
+    let candidates = data
+        .groups
+        .iter()
+        .map(|g| Candidate {
+            item: g,
+            id: &g.id,
+            name: &g.name,
+            qualified: g.name.clone(),
+        })
+        .collect();
+    find_one(candidates, query, Lookup::Any, "group", "use the id")
+}
+
+struct Candidate<'a, T> {
+    item: &'a T,
+    id: &'a str,
+    name: &'a str,
+    /// ショートカットは「グループ名/名前」、グループは名前のまま
+    qualified: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Lookup {
+    /// Exact names only (ignoring case), for `--name`.
+    Name,
+    /// Ids and exact names (ignoring case), for links.
+    IdOrName,
+    /// Ids, exact names, then partial names.
+    Any,
+}
+
+/// id → 名前の完全一致 → 大文字小文字を無視した一致 → 部分一致の順に探す。
+/// 途中の段階で複数見つかった場合は、候補を挙げて曖昧としてエラーにする。
+fn find_one<'a, T>(
+    candidates: Vec<Candidate<'a, T>>,
+    query: &str,
+    lookup: Lookup,
+    kind: &str,
+    hint: &str,
+) -> Result<&'a T, String> {
+    let lower = query.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    let matches = |c: &Candidate<T>, stage: usize| match stage {
+        0 => lookup != Lookup::Name && c.id == query,
+        1 => c.name == query || c.qualified == query,
+        2 => c.name.to_lowercase() == lower || c.qualified.to_lowercase() == lower,
+        _ => {
+            let qualified = c.qualified.to_lowercase();
+            lookup == Lookup::Any
+                && !words.is_empty()
+                && words.iter().all(|word| qualified.contains(word))
+        }
+    };
+
+    for stage in 0..4 {
+        let found: Vec<&Candidate<T>> = candidates.iter().filter(|c| matches(c, stage)).collect();
+        match found.as_slice() {
+            [] => continue,
+            [candidate] => return Ok(candidate.item),
+            _ => {
+                let list: Vec<String> = found
+                    .iter()
+                    .map(|c| format!("  {}  {}", c.id, c.qualified))
+                    .collect();
+                return Err(format!(
+                    "\"{}\" matches {} {}s:\n{}\nTo pick one, {}.",
+                    query,
+                    found.len(),
+                    kind,
+                    list.join("\n"),
+                    hint
+                ));
+            }
+        }
+    }
+    Err(format!("No {} matches \"{}\"", kind, query))
+}
+
+fn sorted_groups(data: &AppData) -> Vec<&Group> {
+    let mut groups: Vec<&Group> = data.groups.iter().collect();
+    groups.sort_by_key(|g| g.order);
+    groups
+}
+
+fn group_name(data: &AppData, group_id: &str) -> String {
+    data.groups
+        .iter()
+        .find(|g| g.id == group_id)
+        .map(|g| g.name.clone())
+        .unwrap_or_else(|| group_id.to_string())
+}
+
+// ========================================
+// Output
+// ========================================
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize output: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn print_json_line<T: Serialize + ?Sized>(value: &T) -> Result<(), String> {
+    let json =
+        serde_json::to_string(value).map_err(|e| format!("Failed to serialize output: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| display_width(h)).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(display_width(cell));
+        }
+    }
+
+    let header: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    for row in std::iter::once(&header).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| {
+                let padding = width - display_width(cell);
+                format!("{}{}", cell, " ".repeat(padding))
+            })
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
+
+/// 端末での表示幅。全角文字（日本語の名前など）は2桁として数える
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+fn describe_step(step: &ActionStep) -> String {
+    let mut text = match &step.action {
+        Action::Launch { path, args, .. } => {
+            let args = args.as_deref().unwrap_or_default().join(" ");
+            format!("launch {} {}", path, args).trim_end().to_string()
+        }
+        Action::Kill { process_name } => format!("kill {}", process_name),
+        Action::OpenFolder { path, .. } => format!("open_folder {}", path),
+        Action::OpenUrl { url, .. } => format!("open_url {}", url),
+        Action::Delay { ms } => format!("delay {}ms", ms),
+    };
+
+    let mut options = Vec::new();
+    if let Some(policy) = &step.on_error {
+        options.push(format!("on error: {}", describe_policy(policy)));
+    }
+    if let Some(timeout_ms) = step.timeout_ms {
+        options.push(format!("timeout: {}ms", timeout_ms));
+    }
+    if !options.is_empty() {
+        text.push_str(&format!("  [{}]", options.join(", ")));
+    }
+    text
+}
+
+fn describe_policy(policy: &ErrorPolicy) -> String {
+    match policy {
+        ErrorPolicy::Abort => "abort".to_string(),
+        ErrorPolicy::Continue => "continue".to_string(),
+        ErrorPolicy::Retry {
+            retries,
+            delay_ms,
+            backoff,
+        } => format!(
+            "retry {} times every {}ms (x{})",
+            retries, delay_ms, backoff
+        ),
+    }
+}
+
+fn print_validation_report(report: &ValidationReport, path: &std::path::Path) {
+    for issue in &report.issues {
+        let severity = match issue.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        println!("{}: {}", severity, issue.message);
+    }
+    let errors = report
+        .issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+    println!(
+        "{}: {} errors, {} warnings",
+        path.display(),
+        errors,
+        report.issues.len() - errors
+    );
+}
+
+fn print_import_report(report: &ImportReport) {
+    println!(
+        "Created {} shortcuts and {} groups, replaced {}, skipped {}, removed {}",
+        report.created_shortcuts,
+        report.created_groups,
+        report.replaced_shortcuts,
+        report.skipped_shortcuts,
+        report.removed_shortcuts
+    );
+    for conflict in &report.conflicts {
+        let location = conflict
+            .group_name
+            .as_ref()
+            .map(|group| format!(" in \"{}\"", group))
+            .unwrap_or_default();
+        let renamed = conflict
+            .new_name
+            .as_ref()
+            .map(|name| format!(" as \"{}\"", name))
+            .unwrap_or_default();
+        println!(
+            "  {} \"{}\"{} already exists: {}{}",
+            conflict.kind,
+            conflict.name,
+            location,
+            conflict.resolution.as_str(),
+            renamed
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{app_data, group, shortcut};
+
+    fn parse_args(args: &[&str]) -> Result<Option<Command>, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn sample_data() -> AppData {
+        app_data(
+            vec![group("home", "Home", 0), group("work", "Work", 1)],
+            vec![
+                shortcut("s1", "Morning setup", "work"),
+                shortcut("s2", "Morning news", "work"),
+                shortcut("s3", "Morning setup", "home"),
+                shortcut("s4", "Backup", "home"),
+                shortcut("s5", "BACKUP", "work"),
+            ],
+        )
+    }
+
+    fn find(query: ShortcutQuery) -> Result<String, String> {
+        find_shortcut(&sample_data(), &query).map(|s| s.id.clone())
+    }
+
+    #[test]
+    fn parse_without_command_opens_the_gui() {
+        assert!(parse_args(&[]).unwrap().is_none());
+        assert!(parse_args(&["--data-dir", "D:/data", "--portable"])
+            .unwrap()
+            .is_none());
+        // 以前から知らないフラグは無視して GUI を開いていた
+        assert!(parse_args(&["--unknown"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn parse_subcommands() {
+        let command = parse_args(&["--profile", "work", "run", "Morning setup", "--json"]);
+        assert!(matches!(
+            command,
+            Ok(Some(Command::Run {
+                query: ShortcutQuery::IdOrName(ref query),
+                dry_run: false,
+                output: Output::Json,
+            })) if query == "Morning setup"
+        ));
+
+        let command = parse_args(&["run", "--name", "Work/Morning setup", "--dry-run"]);
+        assert!(matches!(
+            command,
+            Ok(Some(Command::Run {
+                query: ShortcutQuery::Name(ref name),
+                dry_run: true,
+                output: Output::Text,
+            })) if name == "Work/Morning setup"
+        ));
+
+        assert!(matches!(
+            parse_args(&["list", "groups", "--json"]),
+            Ok(Some(Command::List {
+                groups: true,
+                json: true
+            }))
+        ));
+        assert!(matches!(
+            parse_args(&["run-group", "Work", "--help"]),
+            Ok(Some(Command::Help))
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_subcommands() {
+        let error = |args: &[&str]| parse_args(args).unwrap_err();
+
+        assert_eq!(error(&["frobnicate"]), "Unknown command: frobnicate");
+        assert_eq!(
+            error(&["list", "--dry-run"]),
+            "Unknown option for list: --dry-run"
+        );
+        assert_eq!(error(&["run"]), "Missing <shortcut>");
+        assert_eq!(error(&["run", "a", "b"]), "Unexpected argument: b");
+        assert_eq!(
+            error(&["run", "--name", "a", "b"]),
+            "Unexpected argument: b"
+        );
+        assert_eq!(
+            error(&["run", "a", "--json", "--ndjson"]),
+            "--json and --ndjson cannot be combined"
+        );
+        assert_eq!(error(&["list", "everything"]), "Cannot list \"everything\"");
+        assert_eq!(
+            error(&["import", "bundle.json", "--group", "work"]),
+            "--group is only used with --code"
+        );
+        assert_eq!(
+            error(&["import", "bundle.json", "--groups", "sideways"]),
+            "Invalid value for --groups: sideways"
+        );
+        assert_eq!(
+            error(&["import", "--code", "abc", "--group", "a", "--group", "b"]),
+            "--group can only be given once with --code"
+        );
+        assert_eq!(
+            error(&["import", "--code", "abc", "--on-conflict", "rename"]),
+            "--on-conflict is not used with --code"
+        );
+        assert_eq!(
+            error(&["import", "--code", "abc", "--groups", "merge"]),
+            "--groups is not used with --code"
+        );
+        assert_eq!(error(&["show", "--name"]), "Missing value for --name");
+    }
+
+    #[test]
+    fn parse_legacy_flags() {
+        assert!(matches!(
+            parse_args(&["--execute-shortcut", "s1", "--ndjson"]),
+            Ok(Some(Command::Run {
+                query: ShortcutQuery::Id(ref id),
+                dry_run: false,
+                output: Output::Ndjson,
+            })) if id == "s1"
+        ));
+        assert_eq!(
+            parse_args(&["--execute-shortcut", "s1", "--import-code", "abc"]).unwrap_err(),
+            "--execute-shortcut and --import-code cannot be combined"
+        );
+        assert!(matches!(
+            parse_args(&["--import-code", "abc", "--group", "work"]),
+            Ok(Some(Command::ImportCode { ref group_id, .. })) if group_id.as_deref() == Some("work")
+        ));
+        assert_eq!(
+            parse_args(&["--import-code", "abc", "--group", "a", "--group", "b"]).unwrap_err(),
+            "--group can only be given once with --import-code"
+        );
+        assert_eq!(
+            parse_args(&["--import-code", "abc", "--groups", "replace"]).unwrap_err(),
+            "--groups is not used with --import-code"
+        );
+        assert_eq!(
+            parse_args(&["--execute-shortcut"]).unwrap_err(),
+            "Missing value for --execute-shortcut"
+        );
+    }
+
+    #[test]
+    fn parse_links() {
+        let link = "advanced-shortcut://run/s1?folder=C%3A%5CWork";
+        assert!(matches!(
+            parse_args(&["--data-dir", "D:/data", link]),
+            Ok(Some(Command::OpenLink { link: ref parsed })) if parsed == link
+        ));
+        // スキームの大文字小文字は区別しない
+        assert!(matches!(
+            parse_args(&["Advanced-Shortcut://run/s1"]),
+            Ok(Some(Command::OpenLink { .. }))
+        ));
+        // リンクの後ろにコマンドやフラグを足して実行させることはできない
+        assert_eq!(
+            parse_args(&[link, "--execute-shortcut", "s2"]).unwrap_err(),
+            "Unexpected argument after link: --execute-shortcut"
+        );
+        // リンクはコマンドの引数としては解釈しない
+        assert!(matches!(
+            parse_args(&["run", link]),
+            Ok(Some(Command::Run {
+                query: ShortcutQuery::IdOrName(_),
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn find_shortcut_by_id_and_name() {
+        assert_eq!(find(ShortcutQuery::IdOrName("s2".into())).unwrap(), "s2");
+        assert_eq!(
+            find(ShortcutQuery::IdOrName("Morning news".into())).unwrap(),
+            "s2"
+        );
+        assert_eq!(
+            find(ShortcutQuery::IdOrName("morn NEWS".into())).unwrap(),
+            "s2"
+        );
+        // 完全一致は大文字小文字を無視した一致より優先する
+        assert_eq!(
+            find(ShortcutQuery::IdOrName("Backup".into())).unwrap(),
+            "s4"
+        );
+        assert_eq!(
+            find(ShortcutQuery::IdOrName("BACKUP".into())).unwrap(),
+            "s5"
+        );
+    }
+
+    #[test]
+    fn find_shortcut_by_group_qualified_name() {
+        assert_eq!(
+            find(ShortcutQuery::IdOrName("Work/Morning setup".into())).unwrap(),
+            "s1"
+        );
+        assert_eq!(
+            find(ShortcutQuery::Name("home/morning SETUP".into())).unwrap(),
+            "s3"
+        );
+        assert_eq!(
+            find(ShortcutQuery::IdOrExactName("Home/Morning setup".into())).unwrap(),
+            "s3"
+        );
+        assert_eq!(
+            find(ShortcutQuery::IdOrName("work/back".into())).unwrap(),
+            "s5"
+        );
+    }
+
+    #[test]
+    fn find_shortcut_reports_ambiguous_names() {
+        let error = find(ShortcutQuery::IdOrName("Morning setup".into())).unwrap_err();
+        assert!(error.starts_with("\"Morning setup\" matches 2 shortcuts:"));
+        assert!(error.contains("  s1  Work/Morning setup"));
+        assert!(error.contains("  s3  Home/Morning setup"));
+        assert!(error.contains("group-qualified name"));
+
+        let error = find(ShortcutQuery::IdOrName("morn".into())).unwrap_err();
+        assert!(error.starts_with("\"morn\" matches 3 shortcuts:"));
+
+        let error = find(ShortcutQuery::Name("backup".into())).unwrap_err();
+        assert!(error.starts_with("\"backup\" matches 2 shortcuts:"));
+    }
+
+    #[test]
+    fn exact_lookups_never_match_partial_names() {
+        assert_eq!(
+            find(ShortcutQuery::Name("Morning news".into())).unwrap(),
+            "s2"
+        );
+        assert!(find(ShortcutQuery::Name("news".into())).is_err());
+        assert!(find(ShortcutQuery::IdOrExactName("news".into())).is_err());
+        assert_eq!(
+            find(ShortcutQuery::IdOrExactName("s2".into())).unwrap(),
+            "s2"
+        );
+        // --name は id を受け付けず、--execute-shortcut は名前を受け付けない
+        assert_eq!(
+            find(ShortcutQuery::Name("s2".into())).unwrap_err(),
+            "No shortcut matches \"s2\""
+        );
+        assert_eq!(
+            find(ShortcutQuery::Id("Morning news".into())).unwrap_err(),
+            "Shortcut not found: Morning news"
+        );
+    }
+
+    #[test]
+    fn find_group_by_id_and_name() {
+        let data = sample_data();
+        assert_eq!(find_group(&data, "home").unwrap().id, "home");
+        assert_eq!(find_group(&data, "WORK").unwrap().id, "work");
+        assert_eq!(find_group(&data, "wo").unwrap().id, "work");
+        assert_eq!(
+            find_group(&data, "Play").unwrap_err(),
+            "No group matches \"Play\""
+        );
+    }
+}
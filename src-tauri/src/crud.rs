@@ -0,0 +1,439 @@
+use crate::storage::DEFAULT_GROUP_ID;
+use crate::{ActionStep, AppData, ErrorPolicy, Group, Shortcut};
+use serde::Deserialize;
+
+// ========================================
+// Input Types
+// ========================================
+
+/// ショートカットの作成・更新時にフロントエンドから受け取る内容。
+/// id・作成日時・更新日時・並び順はバックエンドが決める。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutInput {
+    pub name: String,
+    pub icon: String,
+    pub group_id: String,
+    pub actions: Vec<ActionStep>,
+    #[serde(default)]
+    pub on_error: ErrorPolicy,
+    #[serde(default)]
+    pub link_runnable: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupInput {
+    pub name: String,
+    pub color: String,
+    pub icon: String,
+    #[serde(default = "default_expanded")]
+    pub is_expanded: bool,
+}
+
+fn default_expanded() -> bool {
+    true
+}
+
+// ========================================
+// Shortcuts
+// ========================================
+
+/// Add a shortcut at the end of its group. Returns the new id.
+pub fn create_shortcut(data: &mut AppData, input: ShortcutInput) -> Result<String, String> {
+    require_group(data, &input.group_id)?;
+
+    let id = new_id();
+    let now = now_iso();
+    data.shortcuts.push(Shortcut {
+        id: id.clone(),
+        name: input.name,
+        icon: input.icon,
+        order: next_shortcut_order(data, &input.group_id),
+        group_id: input.group_id,
+        actions: input.actions,
+        on_error: input.on_error,
+        link_runnable: input.link_runnable,
+        created_at: now.clone(),
+        updated_at: now,
+    });
+    Ok(id)
+}
+
+/// Replace the editable fields of a shortcut. Moving it to another group
+/// puts it at the end of that group.
+pub fn update_shortcut(data: &mut AppData, id: &str, input: ShortcutInput) -> Result<(), String> {
+    require_group(data, &input.group_id)?;
+    let next_order = next_shortcut_order(data, &input.group_id);
+
+    let shortcut = find_shortcut(data, id)?;
+    let old_group = std::mem::replace(&mut shortcut.group_id, input.group_id);
+    let moved = old_group != shortcut.group_id;
+    if moved {
+        shortcut.order = next_order;
+    }
+    shortcut.name = input.name;
+    shortcut.icon = input.icon;
+    shortcut.actions = input.actions;
+    shortcut.on_error = input.on_error;
+    shortcut.link_runnable = input.link_runnable;
+    shortcut.updated_at = now_iso();
+
+    if moved {
+        normalize_shortcut_order(data, &old_group);
+    }
+    Ok(())
+}
+
+pub fn delete_shortcut(data: &mut AppData, id: &str) -> Result<Shortcut, String> {
+    let index = data
+        .shortcuts
+        .iter()
+        .position(|s| s.id == id)
+        .ok_or_else(|| format!("Shortcut not found: {}", id))?;
+    let removed = data.shortcuts.remove(index);
+    normalize_shortcut_order(data, &removed.group_id);
+    Ok(removed)
+}
+
+/// Move a shortcut to `index` within `group_id` (which may be its current
+/// group). An index past the end appends.
+pub fn move_shortcut(
+    data: &mut AppData,
+    id: &str,
+    group_id: &str,
+    index: usize,
+) -> Result<(), String> {
+    require_group(data, group_id)?;
+
+    let mut ids = shortcut_ids_in(data, group_id);
+    ids.retain(|other| other != id);
+    ids.insert(index.min(ids.len()), id.to_string());
+
+    let shortcut = find_shortcut(data, id)?;
+    let old_group = std::mem::replace(&mut shortcut.group_id, group_id.to_string());
+    shortcut.updated_at = now_iso();
+    if old_group != group_id {
+        normalize_shortcut_order(data, &old_group);
+    }
+    apply_shortcut_order(data, &ids);
+    Ok(())
+}
+
+/// Reorder the shortcuts of a group. `ids` must list every shortcut in it.
+pub fn reorder_shortcuts(data: &mut AppData, group_id: &str, ids: &[String]) -> Result<(), String> {
+    let mut current = shortcut_ids_in(data, group_id);
+    let mut requested = ids.to_vec();
+    current.sort();
+    requested.sort();
+    if current != requested {
+        return Err(format!(
+            "Reorder must list exactly the shortcuts of group {}",
+            group_id
+        ));
+    }
+    apply_shortcut_order(data, ids);
+    Ok(())
+}
+
+// ========================================
+// Groups
+// ========================================
+
+/// Add a group at the end. Returns the new id.
+pub fn create_group(data: &mut AppData, input: GroupInput) -> String {
+    let id = new_id();
+    data.groups.push(Group {
+        id: id.clone(),
+        name: input.name,
+        color: input.color,
+        icon: input.icon,
+        order: next_group_order(data),
+        is_expanded: input.is_expanded,
+    });
+    normalize_group_order(data);
+    id
+}
+
+pub fn update_group(data: &mut AppData, id: &str, input: GroupInput) -> Result<(), String> {
+    let group = find_group(data, id)?;
+    group.name = input.name;
+    group.color = input.color;
+    group.icon = input.icon;
+    group.is_expanded = input.is_expanded;
+    Ok(())
+}
+
+/// Delete a group. Its shortcuts move to the end of the default group, which
+/// itself cannot be deleted. Returns the group and the ids of the moved
+/// shortcuts.
+pub fn delete_group(data: &mut AppData, id: &str) -> Result<(Group, Vec<String>), String> {
+    if id == DEFAULT_GROUP_ID {
+        return Err("The default group cannot be deleted".to_string());
+    }
+    let index = data
+        .groups
+        .iter()
+        .position(|g| g.id == id)
+        .ok_or_else(|| format!("Group not found: {}", id))?;
+    let removed = data.groups.remove(index);
+
+    let moved = shortcut_ids_in(data, id);
+    let first_order = next_shortcut_order(data, DEFAULT_GROUP_ID);
+    for (order, shortcut_id) in (first_order..).zip(&moved) {
+        let shortcut = find_shortcut(data, shortcut_id)?;
+        shortcut.group_id = DEFAULT_GROUP_ID.to_string();
+        shortcut.order = order;
+    }
+    normalize_group_order(data);
+    Ok((removed, moved))
+}
+
+/// Move a group to `index`. An index past the end appends.
+pub fn move_group(data: &mut AppData, id: &str, index: usize) -> Result<(), String> {
+    // 手で編集・取り込みしたファイルでは配列が order 順とは限らないので、先に並べ直す
+    normalize_group_order(data);
+    let from = data
+        .groups
+        .iter()
+        .position(|g| g.id == id)
+        .ok_or_else(|| format!("Group not found: {}", id))?;
+
+    let group = data.groups.remove(from);
+    let index = index.min(data.groups.len());
+    data.groups.insert(index, group);
+    for (order, group) in data.groups.iter_mut().enumerate() {
+        group.order = order as i32;
+    }
+    Ok(())
+}
+
+/// ゴミ箱から戻したショートカットを末尾に追加する。グループが削除済みなら
+/// デフォルトグループへ入れる。
+pub fn restore_shortcut(data: &mut AppData, mut shortcut: Shortcut) -> Result<String, String> {
+    if data.shortcuts.iter().any(|s| s.id == shortcut.id) {
+        return Err(format!("Shortcut already exists: {}", shortcut.id));
+    }
+    if require_group(data, &shortcut.group_id).is_err() {
+        shortcut.group_id = DEFAULT_GROUP_ID.to_string();
+    }
+    shortcut.order = next_shortcut_order(data, &shortcut.group_id);
+    let id = shortcut.id.clone();
+    data.shortcuts.push(shortcut);
+    Ok(id)
+}
+
+/// Put a deleted group back at the end and move the shortcuts it had back
+/// into it (those that still exist).
+pub fn restore_group(
+    data: &mut AppData,
+    group: Group,
+    shortcut_ids: &[String],
+) -> Result<String, String> {
+    if data.groups.iter().any(|g| g.id == group.id) {
+        return Err(format!("Group already exists: {}", group.id));
+    }
+    let id = group.id.clone();
+    data.groups.push(Group {
+        order: next_group_order(data),
+        ..group
+    });
+    normalize_group_order(data);
+
+    let mut touched = Vec::new();
+    for (order, shortcut_id) in (0..).zip(shortcut_ids) {
+        if let Ok(shortcut) = find_shortcut(data, shortcut_id) {
+            let old_group = std::mem::replace(&mut shortcut.group_id, id.clone());
+            shortcut.order = order;
+            touched.push(old_group);
+        }
+    }
+    touched.dedup();
+    for group_id in touched {
+        normalize_shortcut_order(data, &group_id);
+    }
+    normalize_shortcut_order(data, &id);
+    Ok(id)
+}
+
+/// Reorder all groups. `ids` must list every group.
+pub fn reorder_groups(data: &mut AppData, ids: &[String]) -> Result<(), String> {
+    let mut current: Vec<&String> = data.groups.iter().map(|g| &g.id).collect();
+    let mut requested: Vec<&String> = ids.iter().collect();
+    current.sort();
+    requested.sort();
+    if current != requested {
+        return Err("Reorder must list exactly the existing groups".to_string());
+    }
+    for group in &mut data.groups {
+        if let Some(order) = ids.iter().position(|id| *id == group.id) {
+            group.order = order as i32;
+        }
+    }
+    data.groups.sort_by_key(|g| g.order);
+    Ok(())
+}
+
+// ========================================
+// Helpers
+// ========================================
+
+fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Same format as JavaScript's `Date.prototype.toISOString`.
+fn now_iso() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+fn require_group(data: &AppData, group_id: &str) -> Result<(), String> {
+    if data.groups.iter().any(|g| g.id == group_id) {
+        Ok(())
+    } else {
+        Err(format!("Group not found: {}", group_id))
+    }
+}
+
+fn find_shortcut<'a>(data: &'a mut AppData, id: &str) -> Result<&'a mut Shortcut, String> {
+    data.shortcuts
+        .iter_mut()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("Shortcut not found: {}", id))
+}
+
+fn find_group<'a>(data: &'a mut AppData, id: &str) -> Result<&'a mut Group, String> {
+    data.groups
+        .iter_mut()
+        .find(|g| g.id == id)
+        .ok_or_else(|| format!("Group not found: {}", id))
+}
+
+fn next_shortcut_order(data: &AppData, group_id: &str) -> i32 {
+    data.shortcuts
+        .iter()
+        .filter(|s| s.group_id == group_id)
+        .map(|s| s.order + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// 削除や手での編集で order が飛んでいても末尾になる値
+fn next_group_order(data: &AppData) -> i32 {
+    data.groups.iter().map(|g| g.order + 1).max().unwrap_or(0)
+}
+
+/// Ids of a group's shortcuts in display order.
+fn shortcut_ids_in(data: &AppData, group_id: &str) -> Vec<String> {
+    let mut shortcuts: Vec<&Shortcut> = data
+        .shortcuts
+        .iter()
+        .filter(|s| s.group_id == group_id)
+        .collect();
+    shortcuts.sort_by_key(|s| s.order);
+    shortcuts.into_iter().map(|s| s.id.clone()).collect()
+}
+
+fn apply_shortcut_order(data: &mut AppData, ids: &[String]) {
+    for shortcut in &mut data.shortcuts {
+        if let Some(order) = ids.iter().position(|id| *id == shortcut.id) {
+            shortcut.order = order as i32;
+        }
+    }
+}
+
+/// 並び順を 0, 1, 2, ... に詰め直す（表示順は変えない）
+fn normalize_shortcut_order(data: &mut AppData, group_id: &str) {
+    let ids = shortcut_ids_in(data, group_id);
+    apply_shortcut_order(data, &ids);
+}
+
+fn normalize_group_order(data: &mut AppData) {
+    data.groups.sort_by_key(|g| g.order);
+    for (order, group) in data.groups.iter_mut().enumerate() {
+        group.order = order as i32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{app_data, group as named_group, shortcut};
+
+    fn group(id: &str, order: i32) -> Group {
+        named_group(id, id, order)
+    }
+
+    fn group_ids(data: &AppData) -> Vec<&str> {
+        data.groups.iter().map(|g| g.id.as_str()).collect()
+    }
+
+    #[test]
+    fn move_group_with_unsorted_groups() {
+        // 配列の並びと order が食い違っている（order 順は a, b, c, d）
+        let mut data = app_data(
+            vec![group("c", 2), group("a", 0), group("d", 3), group("b", 1)],
+            Vec::new(),
+        );
+
+        move_group(&mut data, "a", 2).unwrap();
+
+        assert_eq!(group_ids(&data), ["b", "c", "a", "d"]);
+        let orders: Vec<i32> = data.groups.iter().map(|g| g.order).collect();
+        assert_eq!(orders, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn move_group_past_the_end_appends() {
+        let mut data = app_data(vec![group("b", 1), group("a", 0)], Vec::new());
+
+        move_group(&mut data, "a", 10).unwrap();
+
+        assert_eq!(group_ids(&data), ["b", "a"]);
+        assert!(move_group(&mut data, "missing", 0).is_err());
+    }
+
+    #[test]
+    fn create_group_with_order_gaps_appends() {
+        // 削除や手での編集で order が飛んでいる
+        let mut data = app_data(vec![group("a", 0), group("b", 5)], Vec::new());
+
+        let id = create_group(
+            &mut data,
+            GroupInput {
+                name: "New".to_string(),
+                color: "#22d3ee".to_string(),
+                icon: "folder".to_string(),
+                is_expanded: true,
+            },
+        );
+
+        assert_eq!(group_ids(&data), ["a", "b", id.as_str()]);
+    }
+
+    #[test]
+    fn restore_group_with_order_gaps_appends() {
+        let mut data = app_data(vec![group("a", 3), group("b", 7)], Vec::new());
+
+        restore_group(&mut data, group("restored", 1), &[]).unwrap();
+
+        assert_eq!(group_ids(&data), ["a", "b", "restored"]);
+        let orders: Vec<i32> = data.groups.iter().map(|g| g.order).collect();
+        assert_eq!(orders, [0, 1, 2]);
+    }
+
+    #[test]
+    fn move_shortcut_within_its_group_updates_it() {
+        let mut data = app_data(
+            vec![group("a", 0)],
+            vec![shortcut("s1", "One", "a"), shortcut("s2", "Two", "a")],
+        );
+        let before = data.shortcuts[0].updated_at.clone();
+
+        move_shortcut(&mut data, "s1", "a", 1).unwrap();
+
+        let moved = data.shortcuts.iter().find(|s| s.id == "s1").unwrap();
+        assert_eq!(moved.order, 1);
+        assert_ne!(moved.updated_at, before);
+    }
+}
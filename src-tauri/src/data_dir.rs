@@ -0,0 +1,214 @@
+use serde::Serialize;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// ========================================
+// Data Location
+// ========================================
+
+/// Environment variable that moves the data folder, e.g. for isolated test
+/// instances.
+pub const DATA_DIR_ENV: &str = "ADVANCED_SHORTCUT_DATA_DIR";
+/// 実行ファイルと同じフォルダにこの名前のファイルがあればポータブルモードで起動する
+pub const PORTABLE_MARKER: &str = "portable";
+/// ポータブルモードのデータフォルダ（実行ファイルのフォルダからの相対パス）
+const PORTABLE_DATA_DIR: &str = "data";
+
+/// どの設定でデータフォルダが決まったか（優先順）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataDirSource {
+    /// `--data-dir <path>`
+    Cli,
+    /// [`DATA_DIR_ENV`]
+    Env,
+    /// `--portable`, or a [`PORTABLE_MARKER`] file next to the executable.
+    Portable,
+    /// `%LOCALAPPDATA%\advanced-shortcut`
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataLocation {
+    pub path: PathBuf,
+    pub source: DataDirSource,
+}
+
+static LOCATION: OnceLock<DataLocation> = OnceLock::new();
+
+/// The data folder for this process. Resolved on first use and fixed for the
+/// lifetime of the process, so every module reads and writes the same place.
+pub fn location() -> &'static DataLocation {
+    LOCATION.get_or_init(|| {
+        let exe_dir = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf));
+        resolve(
+            std::env::args_os().skip(1),
+            std::env::var_os(DATA_DIR_ENV),
+            exe_dir.as_deref(),
+        )
+    })
+}
+
+fn resolve(
+    args: impl Iterator<Item = OsString>,
+    env: Option<OsString>,
+    exe_dir: Option<&Path>,
+) -> DataLocation {
+    let mut cli_dir = None;
+    let mut portable_flag = false;
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("--data-dir") => cli_dir = args.next(),
+            Some("--portable") => portable_flag = true,
+            _ => {}
+        }
+    }
+
+    if let Some(dir) = cli_dir.filter(|dir| !dir.is_empty()) {
+        return DataLocation {
+            path: absolute(PathBuf::from(dir)),
+            source: DataDirSource::Cli,
+        };
+    }
+    if let Some(dir) = env.filter(|dir| !dir.is_empty()) {
+        return DataLocation {
+            path: absolute(PathBuf::from(dir)),
+            source: DataDirSource::Env,
+        };
+    }
+    if let Some(exe_dir) = exe_dir {
+        if portable_flag || exe_dir.join(PORTABLE_MARKER).is_file() {
+            return DataLocation {
+                path: exe_dir.join(PORTABLE_DATA_DIR),
+                source: DataDirSource::Portable,
+            };
+        }
+    }
+
+    DataLocation {
+        path: dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("advanced-shortcut"),
+        source: DataDirSource::Default,
+    }
+}
+
+/// 相対パスは起動時のカレントフォルダ基準で固定する
+fn absolute(path: PathBuf) -> PathBuf {
+    std::path::absolute(&path).unwrap_or(path)
+}
+
+/// Arguments that make another launch of this executable (such as a desktop
+/// shortcut) use the same data folder. Portable and default locations are
+/// found again on their own, so they need none.
+#[cfg(windows)]
+pub fn forwarded_args() -> Vec<String> {
+    let location = location();
+    match location.source {
+        DataDirSource::Cli | DataDirSource::Env => vec![
+            "--data-dir".to_string(),
+            location.path.to_string_lossy().to_string(),
+        ],
+        DataDirSource::Portable | DataDirSource::Default => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = OsString> {
+        list.iter()
+            .map(OsString::from)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// 実行ファイルのフォルダの代わり。`marker` ならポータブルの目印を置く
+    fn exe_dir(name: &str, marker: bool) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "advanced-shortcut-data-dir-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let marker_path = dir.join(PORTABLE_MARKER);
+        if marker {
+            std::fs::write(&marker_path, "").unwrap();
+        } else {
+            let _ = std::fs::remove_file(&marker_path);
+        }
+        dir
+    }
+
+    #[test]
+    fn cli_flag_wins() {
+        let exe = exe_dir("cli", true);
+        let cli = exe.join("from-cli");
+        let location = resolve(
+            [
+                OsString::from("--portable"),
+                "--data-dir".into(),
+                cli.clone().into(),
+            ]
+            .into_iter(),
+            Some(exe.join("from-env").into()),
+            Some(&exe),
+        );
+        assert_eq!(location.source, DataDirSource::Cli);
+        assert_eq!(location.path, cli);
+    }
+
+    #[test]
+    fn env_comes_before_portable() {
+        let exe = exe_dir("env", true);
+        let env = exe.join("from-env");
+        let location = resolve(args(&["--portable"]), Some(env.clone().into()), Some(&exe));
+        assert_eq!(location.source, DataDirSource::Env);
+        assert_eq!(location.path, env);
+    }
+
+    #[test]
+    fn empty_values_are_ignored() {
+        let location = resolve(args(&["--data-dir", ""]), Some("".into()), None);
+        assert_eq!(location.source, DataDirSource::Default);
+    }
+
+    #[test]
+    fn relative_paths_are_made_absolute() {
+        let location = resolve(args(&["--data-dir", "relative"]), None, None);
+        assert!(location.path.is_absolute());
+        assert!(location.path.ends_with("relative"));
+    }
+
+    #[test]
+    fn portable_marker_or_flag() {
+        let exe = exe_dir("marker", true);
+        let location = resolve(args(&[]), None, Some(&exe));
+        assert_eq!(location.source, DataDirSource::Portable);
+        assert_eq!(location.path, exe.join(PORTABLE_DATA_DIR));
+
+        let exe = exe_dir("flag", false);
+        let location = resolve(args(&["--portable"]), None, Some(&exe));
+        assert_eq!(location.source, DataDirSource::Portable);
+        assert_eq!(location.path, exe.join(PORTABLE_DATA_DIR));
+    }
+
+    #[test]
+    fn falls_back_to_the_default() {
+        let exe = exe_dir("default", false);
+        let location = resolve(args(&[]), None, Some(&exe));
+        assert_eq!(location.source, DataDirSource::Default);
+        assert!(location.path.ends_with("advanced-shortcut"));
+
+        // 実行ファイルの場所が分からなければ --portable も使えない
+        let location = resolve(args(&["--portable"]), None, None);
+        assert_eq!(location.source, DataDirSource::Default);
+    }
+}
@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+// ========================================
+// Data Format
+// ========================================
+
+/// データファイル・バンドルの書式。ファイルの拡張子で決まる
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataFormat {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl DataFormat {
+    /// The extension written for this format. `.yml` is also read as YAML.
+    pub fn extension(self) -> &'static str {
+        match self {
+            DataFormat::Json => "json",
+            DataFormat::Yaml => "yaml",
+            DataFormat::Toml => "toml",
+        }
+    }
+
+    /// `None` if the extension is not one of the supported formats.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(DataFormat::Json),
+            "yaml" | "yml" => Some(DataFormat::Yaml),
+            "toml" => Some(DataFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Parse into a JSON value, so migrations and format checks work the same
+    /// for every format.
+    pub fn parse(self, content: &str) -> Result<Value, String> {
+        match self {
+            DataFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            DataFormat::Yaml => serde_norway::from_str(content).map_err(|e| e.to_string()),
+            DataFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// 手で編集しやすいよう、どの書式でも整形して書き出す
+    pub fn serialize<T: Serialize>(self, value: &T) -> Result<String, String> {
+        match self {
+            DataFormat::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            DataFormat::Yaml => serde_norway::to_string(value).map_err(|e| e.to_string()),
+            DataFormat::Toml => toml::to_string_pretty(value).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{group, shortcut, step};
+    use crate::storage::{parse_app_data, serialize_app_data};
+    use crate::{Action, ActionStep, AppData, ErrorPolicy, Shortcut, WindowConfig};
+
+    /// Every action type, with optional fields both set and unset.
+    fn sample() -> AppData {
+        let window = Some(WindowConfig {
+            x: Some(-8),
+            y: Some(0),
+            width: Some(1280),
+            height: None,
+        });
+        AppData {
+            revision: 7,
+            groups: vec![group("default", "デフォルト", 0)],
+            shortcuts: vec![Shortcut {
+                actions: vec![
+                    ActionStep {
+                        action: Action::Launch {
+                            path: r"C:\Program Files\App\app.exe".to_string(),
+                            args: Some(vec!["--new-window".to_string(), "".to_string()]),
+                            window_config: window.clone(),
+                        },
+                        on_error: Some(ErrorPolicy::Retry {
+                            retries: 2,
+                            delay_ms: 500,
+                            backoff: 1.5,
+                        }),
+                        timeout_ms: Some(10000),
+                    },
+                    step(Action::Launch {
+                        path: "notepad.exe".to_string(),
+                        args: None,
+                        window_config: None,
+                    }),
+                    step(Action::Kill {
+                        process_name: "Code.exe".to_string(),
+                    }),
+                    step(Action::OpenFolder {
+                        path: r"C:\Users\me\Documents".to_string(),
+                        window_config: window,
+                    }),
+                    ActionStep {
+                        action: Action::OpenUrl {
+                            url: "https://example.com/?q=a&b=c".to_string(),
+                            window_config: None,
+                        },
+                        on_error: Some(ErrorPolicy::Abort),
+                        timeout_ms: None,
+                    },
+                    step(Action::Delay { ms: 1500 }),
+                ],
+                on_error: ErrorPolicy::Continue,
+                link_runnable: true,
+                updated_at: "2024-01-02T00:00:00.000Z".to_string(),
+                ..shortcut("s1", "作業開始", "default")
+            }],
+        }
+    }
+
+    fn round_trip(format: DataFormat) -> String {
+        let data = sample();
+        let content = serialize_app_data(&data, format).unwrap();
+        let (parsed, _) = parse_app_data(&content, format).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&data).unwrap(),
+            "{:?} round trip changed the data:\n{}",
+            format,
+            content
+        );
+        content
+    }
+
+    #[test]
+    fn json_round_trip() {
+        round_trip(DataFormat::Json);
+    }
+
+    #[test]
+    fn yaml_round_trip() {
+        let content = round_trip(DataFormat::Yaml);
+
+        assert!(content.contains("type: launch"));
+        assert!(content.contains("type: open_folder"));
+        assert!(content.contains("processName: Code.exe"));
+        assert!(content.contains("windowConfig:"));
+        assert!(content.contains("timeoutMs: 10000"));
+        assert!(content.contains("mode: retry"));
+        assert!(content.contains("delayMs: 500"));
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let content = round_trip(DataFormat::Toml);
+
+        assert!(content.contains("type = \"launch\""));
+        assert!(content.contains("type = \"open_url\""));
+        assert!(content.contains("processName = \"Code.exe\""));
+        assert!(content.contains("timeoutMs = 10000"));
+        assert!(content.contains("mode = \"retry\""));
+        // TOML has no null: unset options are left out
+        assert!(!content.contains("height"));
+    }
+
+    #[test]
+    fn reads_hand_written_yaml() {
+        let content = r##"
+revision: 1
+groups:
+  - id: default
+    name: Default
+    color: "#22d3ee"
+    icon: folder
+    order: 0
+    isExpanded: true
+shortcuts:
+  - id: s1
+    name: Morning
+    icon: zap
+    groupId: default
+    order: 0
+    createdAt: ""
+    updatedAt: ""
+    onError:
+      mode: abort
+    actions:
+      - type: launch
+        path: notepad.exe
+        args: [--new-window]
+        timeoutMs: 5000
+      - type: delay
+        ms: 200
+      - type: kill
+        processName: notepad.exe
+"##;
+        let (data, from) = parse_app_data(content, DataFormat::Yaml).unwrap();
+
+        assert_eq!(from, 0);
+        let shortcut = &data.shortcuts[0];
+        assert_eq!(shortcut.on_error, ErrorPolicy::Abort);
+        assert_eq!(shortcut.actions[0].timeout_ms, Some(5000));
+        assert!(matches!(
+            &shortcut.actions[0].action,
+            Action::Launch { args: Some(args), window_config: None, .. } if args == &["--new-window"]
+        ));
+        assert!(matches!(
+            shortcut.actions[1].action,
+            Action::Delay { ms: 200 }
+        ));
+        assert!(matches!(
+            &shortcut.actions[2].action,
+            Action::Kill { process_name } if process_name == "notepad.exe"
+        ));
+    }
+
+    #[test]
+    fn reads_hand_written_toml() {
+        let content = r##"
+schemaVersion = 1
+revision = 1
+
+[[groups]]
+id = "default"
+name = "Default"
+color = "#22d3ee"
+icon = "folder"
+order = 0
+isExpanded = true
+
+[[shortcuts]]
+id = "s1"
+name = "Docs"
+icon = "globe"
+groupId = "default"
+order = 0
+createdAt = ""
+updatedAt = ""
+
+[[shortcuts.actions]]
+type = "open_url"
+url = "https://example.com"
+windowConfig = { width = 800, height = 600 }
+onError = { mode = "retry", retries = 3, delayMs = 100 }
+
+[[shortcuts.actions]]
+type = "open_folder"
+path = 'C:\Users\me'
+"##;
+        let (data, from) = parse_app_data(content, DataFormat::Toml).unwrap();
+
+        assert_eq!(from, 1);
+        let actions = &data.shortcuts[0].actions;
+        assert!(matches!(
+            &actions[0].action,
+            Action::OpenUrl {
+                window_config: Some(WindowConfig {
+                    width: Some(800),
+                    x: None,
+                    ..
+                }),
+                ..
+            }
+        ));
+        assert_eq!(
+            actions[0].on_error,
+            Some(ErrorPolicy::Retry {
+                retries: 3,
+                delay_ms: 100,
+                backoff: 2.0,
+            })
+        );
+        assert!(matches!(
+            &actions[1].action,
+            Action::OpenFolder { path, .. } if path == r"C:\Users\me"
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_action_type() {
+        let content = "revision: 0\ngroups: []\nshortcuts:\n  - id: s1\n    name: x\n    icon: x\n    groupId: default\n    order: 0\n    createdAt: ''\n    updatedAt: ''\n    actions:\n      - type: reboot\n";
+
+        assert!(parse_app_data(content, DataFormat::Yaml).is_err());
+    }
+
+    #[test]
+    fn detects_format_from_extension() {
+        assert_eq!(
+            DataFormat::from_path(Path::new("data.json")),
+            Some(DataFormat::Json)
+        );
+        assert_eq!(
+            DataFormat::from_path(Path::new("data.YML")),
+            Some(DataFormat::Yaml)
+        );
+        assert_eq!(
+            DataFormat::from_path(Path::new("data.toml")),
+            Some(DataFormat::Toml)
+        );
+        assert_eq!(DataFormat::from_path(Path::new("data.txt")), None);
+        assert_eq!(DataFormat::from_path(Path::new("data")), None);
+    }
+}
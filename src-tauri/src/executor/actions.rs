@@ -0,0 +1,402 @@
+use super::{ActionContext, ActionError, ActionOutcome};
+use crate::{Action, WindowConfig};
+use std::process::Command;
+use std::time::Duration;
+use sysinfo::{ProcessesToUpdate, System};
+
+#[cfg(windows)]
+use crate::window_control;
+
+/// アクションを実行する。GUI・CLI・将来の実行経路はすべてここを通る。
+pub(super) fn run(action: &Action, ctx: &ActionContext) -> Result<ActionOutcome, ActionError> {
+    match action {
+        Action::Launch {
+            path,
+            args,
+            window_config,
+        } => launch(path, args.as_deref(), window_config.as_ref(), ctx),
+        Action::Kill { process_name } => kill(process_name),
+        Action::OpenFolder {
+            path,
+            window_config,
+        } => open_folder(path, window_config.as_ref(), ctx),
+        Action::OpenUrl { url, window_config } => open_url(url, window_config.as_ref(), ctx),
+        Action::Delay { ms } => {
+            ctx.sleep(Duration::from_millis(*ms))?;
+            Ok(ActionOutcome::new(format!("Delayed for {}ms", ms)))
+        }
+    }
+}
+
+fn launch(
+    path: &str,
+    args: Option<&[String]>,
+    window_config: Option<&WindowConfig>,
+    ctx: &ActionContext,
+) -> Result<ActionOutcome, ActionError> {
+    // Check if process is already running
+    let exe_name = std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    #[cfg(windows)]
+    {
+        let existing_windows = window_control::find_windows_by_process_name(&exe_name);
+
+        if !existing_windows.is_empty() {
+            let mut outcome =
+                ActionOutcome::new(format!("Adjusted window for already running: {}", exe_name));
+
+            // App already running, just adjust window
+            if let Some(config) = window_config {
+                for hwnd in existing_windows {
+                    if let Some((cur_x, cur_y, cur_w, cur_h)) =
+                        window_control::get_window_rect(hwnd)
+                    {
+                        let x = config.x.unwrap_or(cur_x);
+                        let y = config.y.unwrap_or(cur_y);
+                        let w = config.width.unwrap_or(cur_w);
+                        let h = config.height.unwrap_or(cur_h);
+                        let _ = window_control::set_window_position(hwnd, x, y, w, h);
+                        outcome.hwnds.push(hwnd.0 as i64);
+                    }
+                }
+            }
+            return Ok(outcome);
+        }
+    }
+
+    // Launch new process
+    let mut cmd = Command::new(path);
+    if let Some(arguments) = args {
+        cmd.args(arguments);
+    }
+
+    let child = cmd.spawn().map_err(|e| ActionError::from_spawn(path, &e))?;
+
+    let mut outcome = ActionOutcome::new(format!("Launched: {}", path));
+    outcome.pids.push(child.id());
+
+    // If window config is set, wait a bit and then set window position
+    #[cfg(windows)]
+    if let Some(config) = window_config {
+        let pid = child.id();
+        ctx.sleep(Duration::from_millis(1000))?;
+
+        // Try to find and resize the window
+        for _ in 0..10 {
+            if let Some(hwnd) = window_control::find_window_by_pid(pid) {
+                let x = config.x.unwrap_or(0);
+                let y = config.y.unwrap_or(0);
+                let w = config.width.unwrap_or(800);
+                let h = config.height.unwrap_or(600);
+                let _ = window_control::set_window_position(hwnd, x, y, w, h);
+                outcome.hwnds.push(hwnd.0 as i64);
+                break;
+            }
+            ctx.sleep(Duration::from_millis(200))?;
+        }
+
+        outcome.record_windows_found(path);
+    }
+
+    Ok(outcome)
+}
+
+fn kill(process_name: &str) -> Result<ActionOutcome, ActionError> {
+    let mut sys = System::new_all();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+
+    let mut matched_count = 0;
+    let mut killed_pids = Vec::new();
+    for (pid, process) in sys.processes() {
+        if process.name().to_string_lossy().to_lowercase() == process_name.to_lowercase() {
+            matched_count += 1;
+            if process.kill() {
+                killed_pids.push(pid.as_u32());
+            }
+        }
+    }
+
+    if matched_count == 0 {
+        return Err(ActionError::NotFound {
+            target: process_name.to_string(),
+        });
+    }
+    if killed_pids.is_empty() {
+        return Err(ActionError::PermissionDenied {
+            target: process_name.to_string(),
+            message: format!("Could not kill {} instance(s)", matched_count),
+        });
+    }
+
+    let mut outcome = ActionOutcome::new(format!(
+        "Killed {} instance(s) of {}",
+        killed_pids.len(),
+        process_name
+    ));
+    outcome.pids = killed_pids;
+    Ok(outcome)
+}
+
+fn open_folder(
+    path: &str,
+    window_config: Option<&WindowConfig>,
+    ctx: &ActionContext,
+) -> Result<ActionOutcome, ActionError> {
+    #[cfg(windows)]
+    let before_windows: std::collections::HashSet<isize> = if window_config.is_some() {
+        window_control::get_explorer_windows()
+            .iter()
+            .map(|(hwnd, _)| hwnd.0 as isize)
+            .collect()
+    } else {
+        std::collections::HashSet::new()
+    };
+
+    open::that(path).map_err(|e| ActionError::from_open(path, &e))?;
+
+    let mut outcome = ActionOutcome::new(format!("Opened folder: {}", path));
+
+    // If window config is set, wait and then set window position
+    #[cfg(windows)]
+    if let Some(config) = window_config {
+        // Wait for the window to open
+        ctx.sleep(Duration::from_millis(800))?;
+
+        // Find the new explorer window by comparing before and after
+        let after_windows = window_control::get_explorer_windows();
+
+        // Find windows that weren't there before
+        let new_window = after_windows
+            .iter()
+            .find(|(hwnd, _)| !before_windows.contains(&(hwnd.0 as isize)));
+
+        // If we found a new window, use it; otherwise try to find by folder name
+        let target_hwnd = if let Some((hwnd, _)) = new_window {
+            Some(*hwnd)
+        } else {
+            // Fallback: find window with matching folder name in title
+            let folder_name = std::path::Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            after_windows
+                .iter()
+                .find(|(_, title)| title.contains(&folder_name))
+                .map(|(hwnd, _)| *hwnd)
+        };
+
+        if let Some(hwnd) = target_hwnd {
+            if let Some((cur_x, cur_y, cur_w, cur_h)) = window_control::get_window_rect(hwnd) {
+                let x = config.x.unwrap_or(cur_x);
+                let y = config.y.unwrap_or(cur_y);
+                let w = config.width.unwrap_or(cur_w);
+                let h = config.height.unwrap_or(cur_h);
+                let _ = window_control::set_window_position(hwnd, x, y, w, h);
+                outcome.hwnds.push(hwnd.0 as i64);
+            }
+        }
+
+        outcome.record_windows_found(path);
+    }
+
+    Ok(outcome)
+}
+
+#[cfg(windows)]
+const BROWSER_PROCESSES: [&str; 5] = [
+    "chrome.exe",
+    "msedge.exe",
+    "firefox.exe",
+    "brave.exe",
+    "opera.exe",
+];
+
+fn open_url(
+    url: &str,
+    window_config: Option<&WindowConfig>,
+    ctx: &ActionContext,
+) -> Result<ActionOutcome, ActionError> {
+    #[cfg(windows)]
+    let before_windows: std::collections::HashSet<isize> = if window_config.is_some() {
+        // Get browser windows before opening
+        let mut windows = std::collections::HashSet::new();
+        for browser in BROWSER_PROCESSES {
+            for (hwnd, _) in window_control::get_windows_by_process_name(browser) {
+                windows.insert(hwnd.0 as isize);
+            }
+        }
+        windows
+    } else {
+        std::collections::HashSet::new()
+    };
+
+    // Open URL in new browser window
+    #[cfg(windows)]
+    {
+        use winreg::enums::*;
+        use winreg::RegKey;
+
+        let mut opened = false;
+
+        // Helper function to get browser path from registry
+        fn get_browser_path(prog_id: &str) -> Option<String> {
+            let hkcr = RegKey::predef(HKEY_CLASSES_ROOT);
+            let command_key = format!(r"{}\shell\open\command", prog_id);
+            if let Ok(key) = hkcr.open_subkey(&command_key) {
+                if let Ok(cmd) = key.get_value::<String, _>("") {
+                    // Extract path from command like: "C:\...\chrome.exe" --args
+                    let cmd = cmd.trim();
+                    if let Some(rest) = cmd.strip_prefix('"') {
+                        if let Some(end) = rest.find('"') {
+                            return Some(rest[..end].to_string());
+                        }
+                    } else if let Some(end) = cmd.find(' ') {
+                        return Some(cmd[..end].to_string());
+                    } else {
+                        return Some(cmd.to_string());
+                    }
+                }
+            }
+            None
+        }
+
+        // Try to get default browser from registry
+        if let Ok(hkcu) = RegKey::predef(HKEY_CURRENT_USER).open_subkey(
+            r"Software\Microsoft\Windows\Shell\Associations\UrlAssociations\http\UserChoice",
+        ) {
+            if let Ok(prog_id) = hkcu.get_value::<String, _>("ProgId") {
+                if let Some(browser_path) = get_browser_path(&prog_id) {
+                    let prog_id_lower = prog_id.to_lowercase();
+
+                    // Determine new-window flag based on browser
+                    let new_window_arg = if prog_id_lower.contains("firefox") {
+                        "-new-window"
+                    } else {
+                        "--new-window"
+                    };
+
+                    if Command::new(&browser_path)
+                        .args([new_window_arg, url])
+                        .spawn()
+                        .is_ok()
+                    {
+                        opened = true;
+                    }
+                }
+            }
+        }
+
+        // Fallback: try common browser paths
+        if !opened {
+            let browser_paths = [
+                (
+                    r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+                    "--new-window",
+                ),
+                (
+                    r"C:\Program Files\Microsoft\Edge\Application\msedge.exe",
+                    "--new-window",
+                ),
+                (
+                    r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+                    "--new-window",
+                ),
+                (
+                    r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+                    "--new-window",
+                ),
+                (
+                    r"C:\Program Files\Mozilla Firefox\firefox.exe",
+                    "-new-window",
+                ),
+                (
+                    r"C:\Program Files\BraveSoftware\Brave-Browser\Application\brave.exe",
+                    "--new-window",
+                ),
+            ];
+
+            for (path, arg) in browser_paths {
+                if std::path::Path::new(path).exists()
+                    && Command::new(path).args([arg, url]).spawn().is_ok()
+                {
+                    opened = true;
+                    break;
+                }
+            }
+        }
+
+        // Last resort: use start command (won't guarantee new window)
+        if !opened {
+            Command::new("cmd")
+                .args(["/c", "start", "", url])
+                .spawn()
+                .map_err(|e| ActionError::from_open(url, &e))?;
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        open::that(url).map_err(|e| ActionError::from_open(url, &e))?;
+    }
+
+    let mut outcome = ActionOutcome::new(format!("Opened URL: {}", url));
+
+    // If window config is set, wait and then set window position
+    #[cfg(windows)]
+    if let Some(config) = window_config {
+        ctx.sleep(Duration::from_millis(1500))?;
+
+        // Find the new browser window by checking foreground window
+        let mut target_hwnd: Option<windows::Win32::Foundation::HWND> = None;
+
+        // First try: get the foreground window if it's a browser
+        unsafe {
+            use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+            let fg_hwnd = GetForegroundWindow();
+            if !fg_hwnd.is_invalid() {
+                // Check if foreground window belongs to a browser
+                for browser in BROWSER_PROCESSES {
+                    let browser_windows = window_control::get_windows_by_process_name(browser);
+                    if let Some((hwnd, _)) = browser_windows.iter().find(|(h, _)| h.0 == fg_hwnd.0)
+                    {
+                        target_hwnd = Some(*hwnd);
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Second try: find new window by comparing before/after
+        if target_hwnd.is_none() {
+            for browser in BROWSER_PROCESSES {
+                let after_windows = window_control::get_windows_by_process_name(browser);
+                if let Some((hwnd, _)) = after_windows
+                    .into_iter()
+                    .find(|(h, _)| !before_windows.contains(&(h.0 as isize)))
+                {
+                    target_hwnd = Some(hwnd);
+                    break;
+                }
+            }
+        }
+
+        if let Some(hwnd) = target_hwnd {
+            if let Some((cur_x, cur_y, cur_w, cur_h)) = window_control::get_window_rect(hwnd) {
+                let x = config.x.unwrap_or(cur_x);
+                let y = config.y.unwrap_or(cur_y);
+                let w = config.width.unwrap_or(cur_w);
+                let h = config.height.unwrap_or(cur_h);
+                let _ = window_control::set_window_position(hwnd, x, y, w, h);
+                outcome.hwnds.push(hwnd.0 as i64);
+            }
+        }
+
+        outcome.record_windows_found(url);
+    }
+
+    Ok(outcome)
+}
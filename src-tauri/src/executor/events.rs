@@ -0,0 +1,36 @@
+use super::{ActionResult, RunReport};
+use serde::{Deserialize, Serialize};
+
+/// フロントエンドへ送る実行進捗イベント名
+pub const EXECUTION_PROGRESS_EVENT: &str = "execution-progress";
+
+/// Progress of a single run, keyed by `run_id`. Every run emits
+/// `run_started`, then `action_started`/`action_finished` per executed
+/// action, then `run_finished`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum ExecutionEvent {
+    RunStarted {
+        run_id: String,
+        shortcut_id: String,
+        shortcut_name: String,
+        action_count: usize,
+    },
+    ActionStarted {
+        run_id: String,
+        index: usize,
+        action_type: String,
+    },
+    ActionFinished {
+        run_id: String,
+        result: ActionResult,
+    },
+    RunFinished {
+        run_id: String,
+        report: RunReport,
+    },
+}
@@ -0,0 +1,284 @@
+mod actions;
+mod events;
+mod plan;
+mod result;
+mod run;
+mod vars;
+
+pub use events::{ExecutionEvent, EXECUTION_PROGRESS_EVENT};
+pub(crate) use plan::{find_executable, has_url_scheme};
+pub use plan::{plan_shortcut, RunPlan};
+pub use result::{ActionError, ActionOutcome, ActionResult, RunReport, RunStatus};
+pub(crate) use run::now_ms;
+pub use run::{ActiveRun, CancelToken, RunInfo, RunRegistry};
+pub(crate) use vars::{apply_link_variables, is_variable_name, variable_names};
+
+use crate::{Action, ActionStep, ErrorPolicy, Shortcut};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+// ========================================
+// Execution Runtime
+// ========================================
+
+/// 実行環境ごとの差分（進捗の通知先）を吸収するフック。
+///
+/// GUI と CLI はどちらも同じ `execute_shortcut` / `execute_action` を通り、
+/// ランタイムだけを差し替える。
+pub trait ExecutionRuntime: Send + Sync {
+    /// Called for every progress event of a run, in order.
+    fn emit(&self, _event: &ExecutionEvent) {}
+}
+
+/// Runtime used by the Tauri commands. Forwards progress to the frontend.
+pub struct GuiRuntime {
+    app: AppHandle,
+}
+
+impl GuiRuntime {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl ExecutionRuntime for GuiRuntime {
+    fn emit(&self, event: &ExecutionEvent) {
+        if let Err(e) = self.app.emit(EXECUTION_PROGRESS_EVENT, event) {
+            eprintln!("Failed to emit execution progress: {}", e);
+        }
+    }
+}
+
+/// Runtime used by `--execute-shortcut`. There is no UI to show results in,
+/// so failures are reported on stderr.
+pub struct CliRuntime;
+
+impl ExecutionRuntime for CliRuntime {
+    fn emit(&self, event: &ExecutionEvent) {
+        if let ExecutionEvent::ActionFinished { result, .. } = event {
+            if !result.is_success() {
+                eprintln!(
+                    "Action {} ({}) failed: {}",
+                    result.index + 1,
+                    result.action_type,
+                    result.message
+                );
+            }
+            for warning in &result.warnings {
+                eprintln!(
+                    "Action {} ({}): {}",
+                    result.index + 1,
+                    result.action_type,
+                    warning
+                );
+            }
+        }
+    }
+}
+
+/// Runtime used by `--ndjson`. Every event is printed on stdout as one line
+/// of JSON while the run progresses, for wrappers that follow along.
+pub struct NdjsonRuntime;
+
+impl ExecutionRuntime for NdjsonRuntime {
+    fn emit(&self, event: &ExecutionEvent) {
+        match serde_json::to_string(event) {
+            Ok(line) => println!("{}", line),
+            Err(e) => eprintln!("Failed to serialize execution progress: {}", e),
+        }
+    }
+}
+
+// ========================================
+// Action Context
+// ========================================
+
+/// State shared by everything that waits while an action runs.
+///
+/// All waiting goes through [`ActionContext::sleep`], so a cancelled run or
+/// an expired per-action timeout interrupts delays and window polling alike.
+pub(crate) struct ActionContext<'a> {
+    cancel: &'a CancelToken,
+    deadline: Option<(Instant, u64)>,
+}
+
+impl<'a> ActionContext<'a> {
+    fn new(cancel: &'a CancelToken, timeout_ms: Option<u64>) -> Self {
+        Self {
+            cancel,
+            deadline: timeout_ms.map(|ms| (Instant::now() + Duration::from_millis(ms), ms)),
+        }
+    }
+
+    /// Wait for `duration`, failing early on cancellation or timeout.
+    pub(crate) fn sleep(&self, duration: Duration) -> Result<(), ActionError> {
+        if let Some((deadline, timeout_ms)) = self.deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining <= duration {
+                if self.cancel.wait(remaining) {
+                    return Err(ActionError::Cancelled);
+                }
+                return Err(ActionError::Timeout { timeout_ms });
+            }
+        }
+
+        if self.cancel.wait(duration) {
+            return Err(ActionError::Cancelled);
+        }
+        Ok(())
+    }
+
+    fn check_cancelled(&self) -> Result<(), ActionError> {
+        if self.cancel.is_cancelled() {
+            Err(ActionError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// ========================================
+// Executor
+// ========================================
+
+/// ショートカットのアクションを順番に実行し、実行結果をまとめて返す
+///
+/// 失敗時の振る舞いはアクションごとの `on_error`（未指定ならショートカットの
+/// `on_error`）に従う。中断・キャンセルされた場合、残りのアクションは
+/// `Skipped` になる。
+pub fn execute_shortcut(
+    shortcut: &Shortcut,
+    run: &ActiveRun,
+    runtime: &dyn ExecutionRuntime,
+) -> RunReport {
+    let started = Instant::now();
+    let mut results = Vec::with_capacity(shortcut.actions.len());
+    let mut stopped: Option<RunStatus> = None;
+
+    runtime.emit(&ExecutionEvent::RunStarted {
+        run_id: run.id.clone(),
+        shortcut_id: shortcut.id.clone(),
+        shortcut_name: shortcut.name.clone(),
+        action_count: shortcut.actions.len(),
+    });
+
+    for (index, step) in shortcut.actions.iter().enumerate() {
+        if stopped.is_some() {
+            results.push(ActionResult::skipped(index, step.action.type_name()));
+            continue;
+        }
+
+        let policy = step.on_error.as_ref().unwrap_or(&shortcut.on_error);
+        let result = execute_step(index, step, policy, run, runtime);
+
+        if result.error == Some(ActionError::Cancelled) {
+            stopped = Some(RunStatus::Cancelled);
+        } else if !result.is_success() && *policy != ErrorPolicy::Continue {
+            stopped = Some(RunStatus::Aborted);
+        }
+        results.push(result);
+    }
+
+    let status = stopped.unwrap_or_else(|| {
+        if results.iter().all(ActionResult::is_success) {
+            RunStatus::Succeeded
+        } else {
+            RunStatus::Failed
+        }
+    });
+
+    let report = RunReport {
+        run_id: run.id.clone(),
+        shortcut_id: shortcut.id.clone(),
+        status,
+        started_at_ms: run.started_at_ms,
+        duration_ms: started.elapsed().as_millis() as u64,
+        results,
+    };
+    runtime.emit(&ExecutionEvent::RunFinished {
+        run_id: run.id.clone(),
+        report: report.clone(),
+    });
+    report
+}
+
+/// Run one action, retrying it as long as `policy` allows. The step's
+/// timeout applies to each attempt separately.
+fn execute_step(
+    index: usize,
+    step: &ActionStep,
+    policy: &ErrorPolicy,
+    run: &ActiveRun,
+    runtime: &dyn ExecutionRuntime,
+) -> ActionResult {
+    let cancel = &run.cancel;
+    runtime.emit(&ExecutionEvent::ActionStarted {
+        run_id: run.id.clone(),
+        index,
+        action_type: step.action.type_name().to_string(),
+    });
+
+    let (retries, mut delay_ms, backoff) = match policy {
+        ErrorPolicy::Retry {
+            retries,
+            delay_ms,
+            backoff,
+        } => (*retries, *delay_ms as f64, backoff.max(1.0)),
+        _ => (0, 0.0, 1.0),
+    };
+
+    let started = Instant::now();
+    let mut attempts = 1;
+    let mut outcome = run_attempt(&step.action, step.timeout_ms, cancel);
+    while attempts <= retries {
+        if matches!(outcome, Ok(_) | Err(ActionError::Cancelled)) {
+            break;
+        }
+        let backoff_wait = Duration::from_millis(delay_ms as u64);
+        if let Err(e) = ActionContext::new(cancel, None).sleep(backoff_wait) {
+            outcome = Err(e);
+            break;
+        }
+        delay_ms *= backoff;
+        attempts += 1;
+        outcome = run_attempt(&step.action, step.timeout_ms, cancel);
+    }
+
+    let mut result = ActionResult::new(
+        index,
+        step.action.type_name(),
+        started.elapsed().as_millis() as u64,
+        outcome,
+    );
+    result.attempts = attempts;
+    runtime.emit(&ExecutionEvent::ActionFinished {
+        run_id: run.id.clone(),
+        result: result.clone(),
+    });
+    result
+}
+
+fn run_attempt(
+    action: &Action,
+    timeout_ms: Option<u64>,
+    cancel: &CancelToken,
+) -> Result<ActionOutcome, ActionError> {
+    let ctx = ActionContext::new(cancel, timeout_ms);
+    ctx.check_cancelled()?;
+    actions::run(action, &ctx)
+}
+
+/// 単一のアクションを実行し、所要時間を含む結果を返す
+pub fn execute_action(
+    action: &Action,
+    timeout_ms: Option<u64>,
+    run: &ActiveRun,
+    runtime: &dyn ExecutionRuntime,
+) -> ActionResult {
+    let step = ActionStep {
+        action: action.clone(),
+        on_error: None,
+        timeout_ms,
+    };
+    execute_step(0, &step, &ErrorPolicy::Continue, run, runtime)
+}
@@ -0,0 +1,267 @@
+use super::{vars, ActionError};
+use crate::{Action, ErrorPolicy, Shortcut};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use sysinfo::{ProcessesToUpdate, System};
+
+#[cfg(windows)]
+use crate::window_control;
+
+// ========================================
+// Plan Types
+// ========================================
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedAction {
+    pub index: usize,
+    pub action_type: String,
+    /// What the action would do, in one line.
+    pub summary: String,
+    /// The action with environment variables expanded, for information only:
+    /// running the shortcut passes its values as written, and the plan
+    /// checks those.
+    pub resolved: Action,
+    pub on_error: ErrorPolicy,
+    pub timeout_ms: Option<u64>,
+    /// Processes the action would touch: running instances for `launch`,
+    /// processes that would be killed for `kill`.
+    pub pids: Vec<u32>,
+    pub warnings: Vec<String>,
+    /// The error a real run is expected to fail with.
+    pub error: Option<ActionError>,
+}
+
+/// ドライランの結果。何も起動・終了・移動せずに作る。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunPlan {
+    pub shortcut_id: String,
+    pub shortcut_name: String,
+    /// `false` if any action is expected to fail.
+    pub ok: bool,
+    /// Total time spent in `delay` actions.
+    pub delay_ms: u64,
+    pub actions: Vec<PlannedAction>,
+}
+
+// ========================================
+// Planner
+// ========================================
+
+/// ショートカットを実行せずに、実行した場合の内容を返す
+pub fn plan_shortcut(shortcut: &Shortcut) -> RunPlan {
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+
+    let actions: Vec<PlannedAction> = shortcut
+        .actions
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            let resolved = vars::resolve_action(&step.action, &vars::env_lookup);
+            let mut planned = PlannedAction {
+                index,
+                action_type: step.action.type_name().to_string(),
+                summary: String::new(),
+                resolved: resolved.clone(),
+                on_error: step
+                    .on_error
+                    .clone()
+                    .unwrap_or_else(|| shortcut.on_error.clone()),
+                timeout_ms: step.timeout_ms,
+                pids: Vec::new(),
+                warnings: Vec::new(),
+                error: None,
+            };
+            plan_action(&step.action, &sys, &mut planned);
+            planned
+        })
+        .collect();
+
+    RunPlan {
+        shortcut_id: shortcut.id.clone(),
+        shortcut_name: shortcut.name.clone(),
+        ok: actions.iter().all(|a| a.error.is_none()),
+        delay_ms: actions
+            .iter()
+            .map(|a| match a.resolved {
+                Action::Delay { ms } => ms,
+                _ => 0,
+            })
+            .sum(),
+        actions,
+    }
+}
+
+fn plan_action(action: &Action, sys: &System, planned: &mut PlannedAction) {
+    let variables = variable_names(action);
+    if !variables.is_empty() {
+        planned.warnings.push(format!(
+            "Variables are not expanded when the shortcut runs: {}",
+            variables.join(", ")
+        ));
+    }
+
+    match action {
+        Action::Launch {
+            path,
+            args,
+            window_config,
+        } => {
+            let exe_name = Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            planned.pids = find_process_ids(sys, &exe_name);
+
+            if already_running(&exe_name) {
+                planned.summary = if window_config.is_some() {
+                    format!("Already running: {} (would move its windows)", exe_name)
+                } else {
+                    format!("Already running: {} (nothing to do)", exe_name)
+                };
+                return;
+            }
+
+            match find_executable(path) {
+                Some(resolved) => {
+                    let args = args.as_deref().unwrap_or_default().join(" ");
+                    planned.summary = format!("Launch {} {}", resolved.display(), args)
+                        .trim_end()
+                        .to_string();
+                }
+                None => {
+                    planned.summary = format!("Launch {}", path);
+                    planned.error = Some(ActionError::NotFound {
+                        target: path.clone(),
+                    });
+                }
+            }
+        }
+        Action::Kill { process_name } => {
+            planned.pids = find_process_ids(sys, process_name);
+            if planned.pids.is_empty() {
+                planned.summary = format!("Kill {} (not running)", process_name);
+                planned.error = Some(ActionError::NotFound {
+                    target: process_name.clone(),
+                });
+            } else {
+                planned.summary = format!(
+                    "Kill {} instance(s) of {}",
+                    planned.pids.len(),
+                    process_name
+                );
+            }
+        }
+        Action::OpenFolder { path, .. } => {
+            planned.summary = format!("Open folder {}", path);
+            if !Path::new(path).is_dir() {
+                planned.error = Some(ActionError::NotFound {
+                    target: path.clone(),
+                });
+            }
+        }
+        Action::OpenUrl { url, .. } => {
+            planned.summary = format!("Open URL {}", url);
+            if !has_url_scheme(url) {
+                planned
+                    .warnings
+                    .push("URL has no scheme (e.g. https://)".to_string());
+            }
+        }
+        Action::Delay { ms } => {
+            planned.summary = format!("Wait {}ms", ms);
+        }
+    }
+}
+
+// ========================================
+// Checks
+// ========================================
+
+/// Mirrors the "already running" check of a real launch, which only applies
+/// on Windows.
+fn already_running(exe_name: &str) -> bool {
+    #[cfg(windows)]
+    {
+        !window_control::find_windows_by_process_name(exe_name).is_empty()
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = exe_name;
+        false
+    }
+}
+
+fn find_process_ids(sys: &System, process_name: &str) -> Vec<u32> {
+    let mut pids: Vec<u32> = sys
+        .processes()
+        .iter()
+        .filter(|(_, p)| p.name().to_string_lossy().to_lowercase() == process_name.to_lowercase())
+        .map(|(pid, _)| pid.as_u32())
+        .collect();
+    pids.sort_unstable();
+    pids
+}
+
+/// Resolve `path` the way process creation would: as a file if it contains a
+/// directory part, otherwise by searching `PATH` (with `PATHEXT` on Windows).
+pub(crate) fn find_executable(path: &str) -> Option<PathBuf> {
+    let candidate = Path::new(path);
+    if candidate.is_absolute() || candidate.components().count() > 1 {
+        return candidate.is_file().then(|| candidate.to_path_buf());
+    }
+
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string())
+            .split(';')
+            .map(str::to_string)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let search_path = std::env::var_os("PATH")?;
+    std::env::split_paths(&search_path).find_map(|dir| {
+        let full = dir.join(path);
+        if full.is_file() {
+            return Some(full);
+        }
+        extensions.iter().find_map(|ext| {
+            let with_ext = dir.join(format!("{}{}", path, ext));
+            with_ext.is_file().then_some(with_ext)
+        })
+    })
+}
+
+/// `true` if `url` starts with a scheme such as `https:` or `mailto:`.
+pub(crate) fn has_url_scheme(url: &str) -> bool {
+    match url.split_once(':') {
+        Some((scheme, rest)) => {
+            !rest.is_empty()
+                && scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+fn variable_names(action: &Action) -> Vec<String> {
+    let fields: Vec<&str> = match action {
+        Action::Launch { path, args, .. } => std::iter::once(path.as_str())
+            .chain(args.iter().flatten().map(String::as_str))
+            .collect(),
+        Action::Kill { process_name } => vec![process_name],
+        Action::OpenFolder { path, .. } => vec![path],
+        Action::OpenUrl { url, .. } => vec![url],
+        Action::Delay { .. } => vec![],
+    };
+
+    fields.into_iter().flat_map(vars::variable_names).collect()
+}
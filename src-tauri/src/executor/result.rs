@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// ========================================
+// Action Errors
+// ========================================
+
+/// アクション実行時のエラー。フロントエンドには `kind` タグ付きで渡る。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum ActionError {
+    /// The executable, folder or process does not exist.
+    NotFound { target: String },
+    /// The process could not be started for a reason other than not found.
+    SpawnFailed { target: String, message: String },
+    /// A folder or URL could not be handed to the shell.
+    OpenFailed { target: String, message: String },
+    /// The action did not finish within its time limit.
+    Timeout { timeout_ms: u64 },
+    /// The run was cancelled while this action was running.
+    Cancelled,
+    /// The action ran but the window to position never appeared. Reported as
+    /// a warning of a successful action, not as a failure.
+    WindowNotFound { target: String },
+    /// The OS refused the operation (launching, killing, ...).
+    PermissionDenied { target: String, message: String },
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::NotFound { target } => write!(f, "Not found: {}", target),
+            ActionError::SpawnFailed { target, message } => {
+                write!(f, "Failed to launch {}: {}", target, message)
+            }
+            ActionError::OpenFailed { target, message } => {
+                write!(f, "Failed to open {}: {}", target, message)
+            }
+            ActionError::Timeout { timeout_ms } => write!(f, "Timed out after {}ms", timeout_ms),
+            ActionError::Cancelled => write!(f, "Cancelled"),
+            ActionError::WindowNotFound { target } => write!(f, "Window not found for {}", target),
+            ActionError::PermissionDenied { target, message } => {
+                write!(f, "Permission denied for {}: {}", target, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+impl ActionError {
+    /// Classify an I/O error raised while spawning `target`.
+    pub fn from_spawn(target: &str, error: &std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => ActionError::NotFound {
+                target: target.to_string(),
+            },
+            std::io::ErrorKind::PermissionDenied => ActionError::PermissionDenied {
+                target: target.to_string(),
+                message: error.to_string(),
+            },
+            _ => ActionError::SpawnFailed {
+                target: target.to_string(),
+                message: error.to_string(),
+            },
+        }
+    }
+
+    /// Classify an I/O error raised while opening `target` with the shell.
+    pub fn from_open(target: &str, error: &std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => ActionError::NotFound {
+                target: target.to_string(),
+            },
+            std::io::ErrorKind::PermissionDenied => ActionError::PermissionDenied {
+                target: target.to_string(),
+                message: error.to_string(),
+            },
+            _ => ActionError::OpenFailed {
+                target: target.to_string(),
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+// ========================================
+// Action Results
+// ========================================
+
+/// What an action touched when it succeeded.
+#[derive(Debug, Clone, Default)]
+pub struct ActionOutcome {
+    pub message: String,
+    pub pids: Vec<u32>,
+    pub hwnds: Vec<i64>,
+    /// `None` unless the action has a window config.
+    pub windows_found: Option<bool>,
+    pub warnings: Vec<String>,
+}
+
+impl ActionOutcome {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    /// ウィンドウ設定がある場合に、位置を合わせるウィンドウが見つかったかを記録する。
+    ///
+    /// Not finding one is only a warning: the program or page did open, it may
+    /// just live in the tray or in an already open browser window. Failing
+    /// here would also make `retry` launch it again.
+    #[cfg(windows)]
+    pub fn record_windows_found(&mut self, target: &str) {
+        let found = !self.hwnds.is_empty();
+        self.windows_found = Some(found);
+        if !found {
+            let warning = ActionError::WindowNotFound {
+                target: target.to_string(),
+            };
+            self.warnings.push(warning.to_string());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionStatus {
+    Succeeded,
+    Failed,
+    /// Not run because an earlier action aborted the chain.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionResult {
+    pub index: usize,
+    pub action_type: String,
+    pub status: ActionStatus,
+    pub duration_ms: u64,
+    /// How many times the action was run (more than 1 when retried).
+    pub attempts: u32,
+    pub pids: Vec<u32>,
+    pub hwnds: Vec<i64>,
+    /// Whether the window to position was found; `None` without a window config.
+    #[serde(default)]
+    pub windows_found: Option<bool>,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    pub message: String,
+    pub error: Option<ActionError>,
+}
+
+impl ActionResult {
+    pub fn new(
+        index: usize,
+        action_type: &str,
+        duration_ms: u64,
+        outcome: Result<ActionOutcome, ActionError>,
+    ) -> Self {
+        match outcome {
+            Ok(outcome) => Self {
+                index,
+                action_type: action_type.to_string(),
+                status: ActionStatus::Succeeded,
+                duration_ms,
+                attempts: 1,
+                pids: outcome.pids,
+                hwnds: outcome.hwnds,
+                windows_found: outcome.windows_found,
+                warnings: outcome.warnings,
+                message: outcome.message,
+                error: None,
+            },
+            Err(error) => Self {
+                index,
+                action_type: action_type.to_string(),
+                status: ActionStatus::Failed,
+                duration_ms,
+                attempts: 1,
+                pids: Vec::new(),
+                hwnds: Vec::new(),
+                windows_found: None,
+                warnings: Vec::new(),
+                message: error.to_string(),
+                error: Some(error),
+            },
+        }
+    }
+
+    pub fn skipped(index: usize, action_type: &str) -> Self {
+        Self {
+            index,
+            action_type: action_type.to_string(),
+            status: ActionStatus::Skipped,
+            duration_ms: 0,
+            attempts: 0,
+            pids: Vec::new(),
+            hwnds: Vec::new(),
+            windows_found: None,
+            warnings: Vec::new(),
+            message: "Skipped".to_string(),
+            error: None,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status == ActionStatus::Succeeded
+    }
+}
+
+// ========================================
+// Run Reports
+// ========================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// Every action succeeded.
+    Succeeded,
+    /// At least one action failed but the chain ran to the end.
+    Failed,
+    /// An action failed under an `abort` (or exhausted `retry`) policy.
+    Aborted,
+    /// The run was cancelled via `cancel_shortcut_run` or Ctrl+C.
+    Cancelled,
+}
+
+/// ショートカット1回分の実行結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport {
+    pub run_id: String,
+    pub shortcut_id: String,
+    pub status: RunStatus,
+    pub started_at_ms: u64,
+    pub duration_ms: u64,
+    pub results: Vec<ActionResult>,
+}
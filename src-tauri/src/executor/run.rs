@@ -0,0 +1,143 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// ========================================
+// Cancellation
+// ========================================
+
+/// 実行中のショートカットを中断するためのトークン。
+/// 待機中のスレッドは `cancel` で即座に起こされる。
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<(Mutex<bool>, Condvar)>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        let (cancelled, condvar) = &*self.0;
+        *cancelled.lock().unwrap() = true;
+        condvar.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.0 .0.lock().unwrap()
+    }
+
+    /// Wait for `duration` or until cancelled. Returns `true` if cancelled.
+    pub fn wait(&self, duration: Duration) -> bool {
+        let (cancelled, condvar) = &*self.0;
+        let guard = cancelled.lock().unwrap();
+        let (guard, _) = condvar
+            .wait_timeout_while(guard, duration, |cancelled| !*cancelled)
+            .unwrap();
+        *guard
+    }
+}
+
+// ========================================
+// Run Registry
+// ========================================
+
+/// An execution that has been registered and can be cancelled by id.
+#[derive(Clone)]
+pub struct ActiveRun {
+    pub id: String,
+    pub shortcut_id: String,
+    pub started_at_ms: u64,
+    pub cancel: CancelToken,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunInfo {
+    pub run_id: String,
+    pub shortcut_id: String,
+    pub started_at_ms: u64,
+}
+
+/// 実行中のショートカットを run id で管理する。
+/// GUI では Tauri の managed state として、CLI ではプロセス内で1つ作って使う。
+#[derive(Clone, Default)]
+pub struct RunRegistry {
+    runs: Arc<Mutex<HashMap<String, ActiveRun>>>,
+}
+
+impl RunRegistry {
+    /// Register a new run. The run is removed again when the guard is dropped.
+    pub fn begin(&self, shortcut_id: &str) -> RunGuard {
+        let run = ActiveRun {
+            id: uuid::Uuid::new_v4().to_string(),
+            shortcut_id: shortcut_id.to_string(),
+            started_at_ms: now_ms(),
+            cancel: CancelToken::default(),
+        };
+        self.runs
+            .lock()
+            .unwrap()
+            .insert(run.id.clone(), run.clone());
+
+        RunGuard {
+            registry: self.clone(),
+            run,
+        }
+    }
+
+    /// Cancel a run by id. Returns `false` if no such run is active.
+    pub fn cancel(&self, run_id: &str) -> bool {
+        match self.runs.lock().unwrap().get(run_id) {
+            Some(run) => {
+                run.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn cancel_all(&self) {
+        for run in self.runs.lock().unwrap().values() {
+            run.cancel.cancel();
+        }
+    }
+
+    pub fn active(&self) -> Vec<RunInfo> {
+        let mut runs: Vec<RunInfo> = self
+            .runs
+            .lock()
+            .unwrap()
+            .values()
+            .map(|run| RunInfo {
+                run_id: run.id.clone(),
+                shortcut_id: run.shortcut_id.clone(),
+                started_at_ms: run.started_at_ms,
+            })
+            .collect();
+        runs.sort_by_key(|run| run.started_at_ms);
+        runs
+    }
+}
+
+pub struct RunGuard {
+    registry: RunRegistry,
+    run: ActiveRun,
+}
+
+impl std::ops::Deref for RunGuard {
+    type Target = ActiveRun;
+
+    fn deref(&self) -> &ActiveRun {
+        &self.run
+    }
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        self.registry.runs.lock().unwrap().remove(&self.run.id);
+    }
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
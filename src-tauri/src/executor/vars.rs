@@ -0,0 +1,286 @@
+use crate::{Action, Shortcut};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// パス・引数・URL 中の変数を展開する。
+///
+/// `${NAME}` と Windows 形式の `%NAME%` に対応する。`lookup` で解決できない
+/// 変数はそのまま残すので、`100%` のような文字列も壊さない。
+pub fn expand(input: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    substitute(input, true, lookup)
+}
+
+/// `percent` が false なら `${NAME}` だけを置き換える。置き換えた値はもう一度
+/// 展開しない
+fn substitute(input: &str, percent: bool, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    let starts: &[char] = if percent { &['$', '%'] } else { &['$'] };
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(pos) = rest.find(starts) {
+        output.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+
+        let (name, consumed) = if let Some(body) = tail.strip_prefix("${") {
+            match body.find('}') {
+                Some(end) => (&body[..end], end + 3),
+                None => ("", 0),
+            }
+        } else if let Some(body) = tail.strip_prefix('%').filter(|_| percent) {
+            match body.find('%') {
+                Some(end) => (&body[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            ("", 0)
+        };
+
+        match (is_variable_name(name), consumed) {
+            (true, n) if n > 0 => match lookup(name) {
+                Some(value) => {
+                    output.push_str(&value);
+                    rest = &tail[n..];
+                }
+                None => {
+                    output.push_str(&tail[..n]);
+                    rest = &tail[n..];
+                }
+            },
+            _ => {
+                output.push_str(&tail[..1]);
+                rest = &tail[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+pub fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '(' || c == ')')
+}
+
+/// Names of the variables `input` refers to, in either syntax. Running a
+/// shortcut passes them through as written, so the plan and validation point
+/// them out.
+pub fn variable_names(input: &str) -> Vec<String> {
+    let names = RefCell::new(Vec::new());
+    expand(input, &|name| {
+        names.borrow_mut().push(name.to_string());
+        None
+    });
+    names.into_inner()
+}
+
+/// Look a variable up in the process environment.
+pub fn env_lookup(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Expand variables in every string field of `action` that names a target.
+pub fn resolve_action(action: &Action, lookup: &dyn Fn(&str) -> Option<String>) -> Action {
+    let expand = |value: &String| expand(value, lookup);
+
+    match action {
+        Action::Launch {
+            path,
+            args,
+            window_config,
+        } => Action::Launch {
+            path: expand(path),
+            args: args.as_ref().map(|args| args.iter().map(expand).collect()),
+            window_config: window_config.clone(),
+        },
+        Action::Kill { process_name } => Action::Kill {
+            process_name: expand(process_name),
+        },
+        Action::OpenFolder {
+            path,
+            window_config,
+        } => Action::OpenFolder {
+            path: expand(path),
+            window_config: window_config.clone(),
+        },
+        Action::OpenUrl { url, window_config } => Action::OpenUrl {
+            url: expand(url),
+            window_config: window_config.clone(),
+        },
+        Action::Delay { ms } => Action::Delay { ms: *ms },
+    }
+}
+
+/// リンクの変数（`?name=value`）をショートカットに埋め込む。
+///
+/// Links only fill in launch arguments and URLs, never the program to start
+/// or the process to stop, so a link cannot make a shortcut run something
+/// else. Only `${name}` is replaced, never `%NAME%`, and values are used as
+/// they are (percent-encoded in URLs): running a shortcut does not expand
+/// variables again. Every variable must be used by the shortcut; a misspelt
+/// one is an error rather than silently ignored.
+pub fn apply_link_variables(
+    shortcut: &Shortcut,
+    variables: &BTreeMap<String, String>,
+) -> Result<Shortcut, String> {
+    // `${HOME}` などは環境変数を指すので、リンクから別の値を入れさせない
+    // (Windows ignores the case of environment variable names).
+    if let Some(name) = variables.keys().find(|name| {
+        std::env::vars_os().any(|(key, _)| key.to_string_lossy().eq_ignore_ascii_case(name))
+    }) {
+        return Err(format!(
+            "Link variable \"{}\" has the name of an environment variable",
+            name
+        ));
+    }
+
+    let used = RefCell::new(BTreeSet::new());
+    let lookup = |name: &str| {
+        let value = variables.get(name)?;
+        used.borrow_mut().insert(name.to_string());
+        Some(value.clone())
+    };
+    let url_lookup = |name: &str| {
+        lookup(name).map(|value| utf8_percent_encode(&value, NON_ALPHANUMERIC).to_string())
+    };
+
+    let mut shortcut = shortcut.clone();
+    for step in &mut shortcut.actions {
+        match &mut step.action {
+            Action::Launch {
+                args: Some(args), ..
+            } => {
+                for arg in args {
+                    *arg = substitute(arg, false, &lookup);
+                }
+            }
+            Action::OpenUrl { url, .. } => *url = substitute(url, false, &url_lookup),
+            _ => {}
+        }
+    }
+
+    let used = used.into_inner();
+    match variables.keys().find(|name| !used.contains(*name)) {
+        Some(unused) => Err(format!(
+            "Shortcut \"{}\" has no argument or URL that uses ${{{}}}",
+            shortcut.name, unused
+        )),
+        None => Ok(shortcut),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, step};
+
+    fn shortcut(actions: Vec<Action>) -> Shortcut {
+        Shortcut {
+            actions: actions.into_iter().map(step).collect(),
+            ..fixtures::shortcut("s1", "Open report", "default")
+        }
+    }
+
+    fn variables(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn apply(actions: Vec<Action>, pairs: &[(&str, &str)]) -> Result<Vec<Action>, String> {
+        apply_link_variables(&shortcut(actions), &variables(pairs)).map(|shortcut| {
+            shortcut
+                .actions
+                .into_iter()
+                .map(|step| step.action)
+                .collect()
+        })
+    }
+
+    fn launch(args: &[&str]) -> Action {
+        Action::Launch {
+            path: "${link_file}".to_string(),
+            args: Some(args.iter().map(|arg| arg.to_string()).collect()),
+            window_config: None,
+        }
+    }
+
+    fn launch_args(action: &Action) -> Vec<String> {
+        match action {
+            Action::Launch { args, .. } => args.clone().unwrap_or_default(),
+            _ => panic!("not a launch action: {:?}", action),
+        }
+    }
+
+    #[test]
+    fn finds_variable_names_in_both_syntaxes() {
+        assert_eq!(
+            variable_names(r"%LOCALAPPDATA%\App\${NAME}.exe"),
+            ["LOCALAPPDATA", "NAME"]
+        );
+        assert!(variable_names("100% off, 20% more, ${}").is_empty());
+    }
+
+    #[test]
+    fn link_variables_fill_arguments_and_urls() {
+        let actions = apply(
+            vec![
+                launch(&["--open", "${link_file}", "100%"]),
+                Action::OpenUrl {
+                    url: "https://example.com/search?q=${link_file}".to_string(),
+                    window_config: None,
+                },
+            ],
+            &[("link_file", "a b&c.docx")],
+        )
+        .unwrap();
+
+        assert_eq!(launch_args(&actions[0]), ["--open", "a b&c.docx", "100%"]);
+        // 起動するプログラムのパスは変えない
+        assert!(matches!(&actions[0], Action::Launch { path, .. } if path == "${link_file}"));
+        assert!(matches!(
+            &actions[1],
+            Action::OpenUrl { url, .. } if url == "https://example.com/search?q=a%20b%26c%2Edocx"
+        ));
+    }
+
+    #[test]
+    fn link_variables_are_not_expanded_again() {
+        let actions = apply(
+            vec![launch(&["${link_a}", "${link_b}"])],
+            &[("link_a", "${link_b}"), ("link_b", "%PATH%")],
+        )
+        .unwrap();
+
+        assert_eq!(launch_args(&actions[0]), ["${link_b}", "%PATH%"]);
+    }
+
+    #[test]
+    fn link_variables_ignore_percent_syntax() {
+        let error = apply(vec![launch(&["%link_file%"])], &[("link_file", "x")]).unwrap_err();
+        assert_eq!(
+            error,
+            "Shortcut \"Open report\" has no argument or URL that uses ${link_file}"
+        );
+    }
+
+    #[test]
+    fn link_variables_may_not_shadow_environment_variables() {
+        let (name, _) = std::env::vars().next().expect("no environment variables");
+        let name = name.to_lowercase();
+        let placeholder = format!("${{{}}}", name);
+
+        let error = apply(vec![launch(&[&placeholder])], &[(&name, "x")]).unwrap_err();
+        assert_eq!(
+            error,
+            format!(
+                "Link variable \"{}\" has the name of an environment variable",
+                name
+            )
+        );
+    }
+}
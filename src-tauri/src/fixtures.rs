@@ -0,0 +1,48 @@
+//! テスト用のショートカット・グループ。フィールドを追加したときに直すのはここだけにする
+
+use crate::{Action, ActionStep, AppData, ErrorPolicy, Group, Shortcut};
+
+/// An empty shortcut with default settings. Tests that need more use struct
+/// update syntax (`Shortcut { actions, ..shortcut("s1", "A", "g1") }`).
+pub fn shortcut(id: &str, name: &str, group_id: &str) -> Shortcut {
+    Shortcut {
+        id: id.to_string(),
+        name: name.to_string(),
+        icon: "zap".to_string(),
+        group_id: group_id.to_string(),
+        actions: Vec::new(),
+        on_error: ErrorPolicy::default(),
+        link_runnable: false,
+        order: 0,
+        created_at: "2024-01-01T00:00:00.000Z".to_string(),
+        updated_at: "2024-01-01T00:00:00.000Z".to_string(),
+    }
+}
+
+pub fn group(id: &str, name: &str, order: i32) -> Group {
+    Group {
+        id: id.to_string(),
+        name: name.to_string(),
+        color: "#22d3ee".to_string(),
+        icon: "folder".to_string(),
+        order,
+        is_expanded: true,
+    }
+}
+
+/// An action without its own error policy or timeout.
+pub fn step(action: Action) -> ActionStep {
+    ActionStep {
+        action,
+        on_error: None,
+        timeout_ms: None,
+    }
+}
+
+pub fn app_data(groups: Vec<Group>, shortcuts: Vec<Shortcut>) -> AppData {
+    AppData {
+        revision: 0,
+        shortcuts,
+        groups,
+    }
+}
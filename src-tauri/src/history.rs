@@ -0,0 +1,213 @@
+use crate::executor::{ActionResult, RunReport, RunStatus};
+use crate::Shortcut;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// ========================================
+// History Types
+// ========================================
+
+/// どこから実行されたか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunSource {
+    /// Started from the app window.
+    Gui,
+    /// Started with `--execute-shortcut` (desktop `.lnk` files).
+    Cli,
+    /// Started by an external trigger such as a link.
+    Trigger,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub run_id: String,
+    pub shortcut_id: String,
+    /// Name at the time of the run, so entries stay readable after renames.
+    pub shortcut_name: String,
+    pub source: RunSource,
+    pub status: RunStatus,
+    pub started_at_ms: u64,
+    pub duration_ms: u64,
+    pub results: Vec<ActionResult>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRetention {
+    /// Keep at most this many entries (newest first).
+    pub max_entries: usize,
+    /// Drop entries older than this many days. `0` keeps entries forever.
+    pub max_age_days: u32,
+}
+
+impl Default for HistoryRetention {
+    fn default() -> Self {
+        Self {
+            max_entries: 1000,
+            max_age_days: 90,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryFile {
+    #[serde(default)]
+    retention: HistoryRetention,
+    /// Oldest first.
+    #[serde(default)]
+    entries: Vec<HistoryEntry>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryQuery {
+    pub shortcut_id: Option<String>,
+    pub source: Option<RunSource>,
+    pub status: Option<RunStatus>,
+    /// Only entries started at or after this time.
+    pub since_ms: Option<u64>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneRequest {
+    /// Remove entries started before this time.
+    pub before_ms: Option<u64>,
+    /// Remove only entries of this shortcut.
+    pub shortcut_id: Option<String>,
+}
+
+// ========================================
+// History Store
+// ========================================
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+fn get_history_path() -> PathBuf {
+    crate::get_data_dir().join("history.json")
+}
+
+/// 読み込み → 変更 → 保存の間は history.json.lock を保持する。GUI と CLI
+/// （別プロセス）が同時に実行結果を書き込んでも、どちらかの記録が失われない。
+/// 読むだけでも、壊れたファイルを退避することがあるのでロックを取る。
+fn lock_history() -> Result<crate::storage::FileLock, String> {
+    crate::storage::lock_file(&crate::get_data_dir().join("history.json.lock"), "history")
+}
+
+fn load_history_file() -> Result<HistoryFile, String> {
+    crate::storage::load_json_file(&get_history_path())
+}
+
+fn save_history_file(file: &HistoryFile) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+    crate::storage::write_atomic(&get_history_path(), json.as_bytes())
+        .map_err(|e| format!("Failed to write history: {}", e))
+}
+
+fn apply_retention(file: &mut HistoryFile, now_ms: u64) {
+    let retention = file.retention;
+    if retention.max_age_days > 0 {
+        let cutoff = now_ms.saturating_sub(retention.max_age_days as u64 * DAY_MS);
+        file.entries.retain(|entry| entry.started_at_ms >= cutoff);
+    }
+    if file.entries.len() > retention.max_entries {
+        let excess = file.entries.len() - retention.max_entries;
+        file.entries.drain(..excess);
+    }
+}
+
+/// 実行結果を履歴に追記する
+pub fn record_run(
+    shortcut: &Shortcut,
+    report: &RunReport,
+    source: RunSource,
+) -> Result<(), String> {
+    let _lock = lock_history()?;
+    let mut file = load_history_file()?;
+
+    file.entries.push(HistoryEntry {
+        run_id: report.run_id.clone(),
+        shortcut_id: shortcut.id.clone(),
+        shortcut_name: shortcut.name.clone(),
+        source,
+        status: report.status,
+        started_at_ms: report.started_at_ms,
+        duration_ms: report.duration_ms,
+        results: report.results.clone(),
+    });
+    apply_retention(&mut file, crate::executor::now_ms());
+
+    save_history_file(&file)
+}
+
+/// Entries matching `query`, newest first.
+pub fn query(query: &HistoryQuery) -> Result<Vec<HistoryEntry>, String> {
+    let file = {
+        let _lock = lock_history()?;
+        load_history_file()?
+    };
+
+    Ok(file
+        .entries
+        .into_iter()
+        .rev()
+        .filter(|entry| {
+            query
+                .shortcut_id
+                .as_ref()
+                .is_none_or(|id| &entry.shortcut_id == id)
+                && query.source.is_none_or(|source| entry.source == source)
+                && query.status.is_none_or(|status| entry.status == status)
+                && query
+                    .since_ms
+                    .is_none_or(|since| entry.started_at_ms >= since)
+        })
+        .skip(query.offset.unwrap_or(0))
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect())
+}
+
+/// Remove matching entries. With an empty request, clears the whole history.
+/// Returns how many entries were removed.
+pub fn prune(request: &PruneRequest) -> Result<usize, String> {
+    let _lock = lock_history()?;
+    let mut file = load_history_file()?;
+    let before = file.entries.len();
+
+    file.entries.retain(|entry| {
+        let matches_shortcut = request
+            .shortcut_id
+            .as_ref()
+            .is_none_or(|id| &entry.shortcut_id == id);
+        let matches_time = request
+            .before_ms
+            .is_none_or(|before_ms| entry.started_at_ms < before_ms);
+        !(matches_shortcut && matches_time)
+    });
+
+    let removed = before - file.entries.len();
+    if removed > 0 {
+        save_history_file(&file)?;
+    }
+    Ok(removed)
+}
+
+pub fn get_retention() -> Result<HistoryRetention, String> {
+    let _lock = lock_history()?;
+    Ok(load_history_file()?.retention)
+}
+
+/// Update the retention limits and apply them immediately.
+pub fn set_retention(retention: HistoryRetention) -> Result<(), String> {
+    let _lock = lock_history()?;
+    let mut file = load_history_file()?;
+    file.retention = retention;
+    apply_retention(&mut file, crate::executor::now_ms());
+    save_history_file(&file)
+}
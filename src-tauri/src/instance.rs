@@ -0,0 +1,361 @@
+use crate::executor::{self, ActiveRun, ExecutionEvent, ExecutionRuntime, RunRegistry, RunReport};
+use crate::history::RunSource;
+use crate::{data_dir, load_app_data, profiles, record_history, storage};
+#[cfg(not(windows))]
+use interprocess::local_socket::GenericFilePath;
+#[cfg(windows)]
+use interprocess::local_socket::GenericNamespaced;
+use interprocess::local_socket::{
+    prelude::*, Listener, ListenerOptions, Name, RecvHalf, SendHalf, Stream,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+// ========================================
+// Protocol
+// ========================================
+
+// 起動中のアプリとコマンドラインの間は、1行1メッセージの JSON でやり取りする
+
+/// コマンドライン → 起動中のアプリ
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+enum ClientMessage {
+    /// Run a shortcut. Sent once, right after connecting.
+    Run {
+        shortcut_id: String,
+        data_dir: String,
+        profile: String,
+        /// Variables of a link (`?name=value`); empty for `run`.
+        #[serde(default)]
+        variables: BTreeMap<String, String>,
+        /// How the run was started. Runs from links are checked against the
+        /// shortcut as saved in the app.
+        #[serde(default = "default_source")]
+        source: RunSource,
+    },
+    /// Ctrl+C was pressed in the calling terminal.
+    Cancel,
+}
+
+fn default_source() -> RunSource {
+    RunSource::Cli
+}
+
+/// 起動中のアプリ → コマンドライン
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+enum ServerMessage {
+    /// The app uses another data folder or profile, or the shortcut needs
+    /// the confirmation of a link run; the caller runs the shortcut itself.
+    Declined,
+    Progress {
+        event: ExecutionEvent,
+    },
+    Finished {
+        report: RunReport,
+    },
+    /// The shortcut could not be run (e.g. it no longer exists).
+    Failed {
+        message: String,
+    },
+}
+
+/// One name per data folder, so instances started with different
+/// `--data-dir` values do not answer for each other.
+///
+/// Windows では名前付きパイプを使う（既定のアクセス権では他のユーザーは書き込めない）。
+/// Elsewhere the socket is a file in a directory only this user can enter,
+/// because abstract sockets on Linux accept connections from every user.
+fn socket_name() -> io::Result<Name<'static>> {
+    let root = data_dir::location().path.to_string_lossy().to_lowercase();
+    let id = format!(
+        "advanced-shortcut-{:016x}.sock",
+        storage::fingerprint(root.as_bytes())
+    );
+    #[cfg(windows)]
+    {
+        id.to_ns_name::<GenericNamespaced>()
+    }
+    #[cfg(not(windows))]
+    {
+        socket_dir()?.join(id).to_fs_name::<GenericFilePath>()
+    }
+}
+
+/// XDG_RUNTIME_DIR（本人専用）か、一時フォルダに作る本人専用のフォルダ
+#[cfg(not(windows))]
+fn socket_dir() -> io::Result<std::path::PathBuf> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    if let Some(dir) = dirs::runtime_dir() {
+        return Ok(dir);
+    }
+
+    let uid = unsafe { libc::geteuid() };
+    let dir = std::env::temp_dir().join(format!("advanced-shortcut-{}", uid));
+    match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(e),
+    }
+    // 他のユーザーが先に作ったフォルダやリンクは使わない
+    let metadata = std::fs::symlink_metadata(&dir)?;
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not private to this user", dir.display()),
+        ));
+    }
+    Ok(dir)
+}
+
+/// Only this user may connect. A socket file left behind by an instance that
+/// crashed is replaced, but never the socket of an instance still running.
+#[cfg(not(windows))]
+fn listen(name: Name<'static>) -> io::Result<Listener> {
+    use interprocess::os::unix::local_socket::ListenerOptionsExt;
+
+    if Stream::connect(name.borrow()).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            "another instance is already listening",
+        ));
+    }
+    let options = || {
+        ListenerOptions::new()
+            .name(name.borrow())
+            .try_overwrite(true)
+    };
+    match options().mode(0o600).create_sync() {
+        // macOS などはモードを指定できないが、フォルダが本人専用なので足りる
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => options().create_sync(),
+        result => result,
+    }
+}
+
+#[cfg(windows)]
+fn listen(name: Name<'static>) -> io::Result<Listener> {
+    ListenerOptions::new().name(name).create_sync()
+}
+
+/// Whether the other end of `stream` runs as this user. The socket already
+/// keeps other users out; this guards against a socket directory that is
+/// shared after all.
+#[cfg(not(windows))]
+fn is_same_user(stream: &Stream) -> bool {
+    let uid = unsafe { libc::geteuid() };
+    stream
+        .peer_creds()
+        .is_ok_and(|creds| creds.euid() == Some(uid))
+}
+
+#[cfg(windows)]
+fn is_same_user(_stream: &Stream) -> bool {
+    true
+}
+
+fn write_message(writer: &mut impl Write, message: &impl Serialize) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()
+}
+
+// ========================================
+// Server (GUI)
+// ========================================
+
+/// コマンドラインからの実行要求の受け付けを開始する。受け付けはアプリ終了まで
+/// 専用スレッドで続く。別のインスタンスが既に受け付けていれば何もしない。
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        let listener = match socket_name().and_then(listen) {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Failed to listen for command-line requests: {}", e);
+                return;
+            }
+        };
+
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) if !is_same_user(&stream) => {
+                    eprintln!("Rejected a command-line request from another user");
+                }
+                Ok(stream) => {
+                    let app = app.clone();
+                    std::thread::spawn(move || {
+                        if let Err(e) = serve(&app, stream) {
+                            eprintln!("Failed to serve command-line request: {}", e);
+                        }
+                    });
+                }
+                Err(e) => eprintln!("Failed to accept command-line request: {}", e),
+            }
+        }
+    });
+}
+
+/// Forwards progress to both the app window and the waiting command line.
+struct ForwardRuntime<'a> {
+    gui: executor::GuiRuntime,
+    client: &'a Mutex<SendHalf>,
+}
+
+impl ExecutionRuntime for ForwardRuntime<'_> {
+    fn emit(&self, event: &ExecutionEvent) {
+        self.gui.emit(event);
+        // 書き込めない＝コマンドラインが終了した。キャンセルは受信側で行う
+        let progress = ServerMessage::Progress {
+            event: event.clone(),
+        };
+        write_message(&mut *self.client.lock().unwrap(), &progress).ok();
+    }
+}
+
+fn serve(app: &AppHandle, stream: Stream) -> io::Result<()> {
+    let (recv, send) = stream.split();
+    let send = Mutex::new(send);
+    let reply = |message: &ServerMessage| write_message(&mut *send.lock().unwrap(), message);
+
+    let mut lines = BufReader::new(recv).lines();
+    let Some(line) = lines.next() else {
+        return Ok(());
+    };
+    let ClientMessage::Run {
+        shortcut_id,
+        data_dir,
+        profile,
+        variables,
+        source,
+    } = serde_json::from_str(&line?)?
+    else {
+        return Ok(());
+    };
+
+    let same_data = data_dir == data_dir::location().path.to_string_lossy();
+    if !same_data || profile != profiles::active() {
+        return reply(&ServerMessage::Declined);
+    }
+
+    let shortcut = match load_app_data().and_then(|data| {
+        let shortcut = data
+            .shortcuts
+            .into_iter()
+            .find(|s| s.id == shortcut_id)
+            .ok_or_else(|| format!("Shortcut not found: {}", shortcut_id))?;
+        executor::apply_link_variables(&shortcut, &variables)
+    }) {
+        Ok(shortcut) => shortcut,
+        Err(message) => return reply(&ServerMessage::Failed { message }),
+    };
+
+    // リンクからの実行は、保存されているショートカットが許可している場合だけ引き受ける。
+    // Otherwise the caller asks for confirmation and runs it itself, so a
+    // client cannot skip the confirmation by claiming it was given.
+    if source == RunSource::Trigger && !shortcut.link_runnable {
+        return reply(&ServerMessage::Declined);
+    }
+
+    // GUI から実行した場合と同じく、実行中の一覧に載せて画面からもキャンセルできるようにする
+    let registry = app.state::<RunRegistry>().inner().clone();
+    let run = registry.begin(&shortcut.id);
+
+    // Ctrl+C が届くか接続が切れたら（コマンドラインが終了したら）キャンセルする
+    let cancel = run.cancel.clone();
+    std::thread::spawn(move || {
+        for line in lines {
+            let message = line.ok().and_then(|l| serde_json::from_str(&l).ok());
+            if !matches!(message, Some(ClientMessage::Run { .. })) {
+                break;
+            }
+        }
+        cancel.cancel();
+    });
+
+    let runtime = ForwardRuntime {
+        gui: executor::GuiRuntime::new(app.clone()),
+        client: &send,
+    };
+    let report = executor::execute_shortcut(&shortcut, &run, &runtime);
+    record_history(&shortcut, &report, source);
+    reply(&ServerMessage::Finished { report })
+}
+
+// ========================================
+// Client (command line)
+// ========================================
+
+/// 起動中のアプリにショートカットを実行させ、終わるまで待つ。進捗は `runtime` に流す。
+///
+/// Returns `None` if no instance is running or it uses another data folder
+/// or profile; the caller then runs the shortcut itself. Cancelling `run`
+/// (Ctrl+C) cancels the run in the app. `variables` are the variables of a
+/// link, applied by the app to the shortcut as it is saved there.
+pub fn forward_run(
+    shortcut_id: &str,
+    variables: &BTreeMap<String, String>,
+    source: RunSource,
+    run: &ActiveRun,
+    runtime: &dyn ExecutionRuntime,
+) -> Option<Result<RunReport, String>> {
+    let stream = Stream::connect(socket_name().ok()?).ok()?;
+    let (recv, mut send) = stream.split();
+
+    let request = ClientMessage::Run {
+        shortcut_id: shortcut_id.to_string(),
+        data_dir: data_dir::location().path.to_string_lossy().to_string(),
+        profile: profiles::active(),
+        variables: variables.clone(),
+        source,
+    };
+    write_message(&mut send, &request).ok()?;
+
+    let finished = AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            while !finished.load(Ordering::Relaxed) {
+                if run.cancel.wait(Duration::from_millis(100)) {
+                    write_message(&mut send, &ClientMessage::Cancel).ok();
+                    break;
+                }
+            }
+        });
+        let result = receive(recv, runtime);
+        finished.store(true, Ordering::Relaxed);
+        result
+    })
+}
+
+fn receive(recv: RecvHalf, runtime: &dyn ExecutionRuntime) -> Option<Result<RunReport, String>> {
+    for line in BufReader::new(recv).lines() {
+        let message = line
+            .map_err(|e| e.to_string())
+            .and_then(|line| serde_json::from_str(&line).map_err(|e| e.to_string()));
+        match message {
+            Ok(ServerMessage::Declined) => return None,
+            Ok(ServerMessage::Progress { event }) => runtime.emit(&event),
+            Ok(ServerMessage::Finished { report }) => return Some(Ok(report)),
+            Ok(ServerMessage::Failed { message }) => return Some(Err(message)),
+            Err(e) => return Some(Err(format!("Lost connection to the running app: {}", e))),
+        }
+    }
+    // 途中まで実行されているかもしれないので、ここで実行し直すことはしない
+    Some(Err(
+        "The running app closed the connection before the run finished".to_string(),
+    ))
+}
@@ -0,0 +1,187 @@
+use crate::storage;
+use crate::trash::TrashDelta;
+use crate::{AppData, Group, Shortcut};
+use serde::Serialize;
+use std::sync::Mutex;
+
+// ========================================
+// Journal Types
+// ========================================
+
+/// 元に戻す／やり直しの対象になった操作の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    CreateShortcut,
+    UpdateShortcut,
+    DeleteShortcut,
+    MoveShortcut,
+    ReorderShortcuts,
+    CreateGroup,
+    UpdateGroup,
+    DeleteGroup,
+    MoveGroup,
+    ReorderGroups,
+    RestoreFromTrash,
+    ImportBundle,
+    /// Whole-list saves (`save_shortcuts`, `save_groups`, `save_app_data_cmd`).
+    Save,
+}
+
+/// What the next undo and redo would revert or reapply.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoStatus {
+    pub undo: Option<ChangeKind>,
+    pub redo: Option<ChangeKind>,
+}
+
+/// The parts of `AppData` a change can touch (the revision is tracked
+/// separately).
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    shortcuts: Vec<Shortcut>,
+    groups: Vec<Group>,
+}
+
+impl Snapshot {
+    pub fn of(data: &AppData) -> Self {
+        Self {
+            shortcuts: data.shortcuts.clone(),
+            groups: data.groups.clone(),
+        }
+    }
+
+    fn restore(&self, data: &mut AppData) {
+        data.shortcuts = self.shortcuts.clone();
+        data.groups = self.groups.clone();
+    }
+}
+
+struct Change {
+    kind: ChangeKind,
+    before: Snapshot,
+    after: Snapshot,
+    trash: TrashDelta,
+    /// Revision on disk while this change is the one to undo (or redo).
+    /// Anything else means data.json changed in between.
+    revision: u64,
+}
+
+#[derive(Default)]
+struct Journal {
+    /// Oldest first.
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+}
+
+// ========================================
+// Journal
+// ========================================
+
+/// 変更履歴はメモリ上にだけ持つ（アプリを終了すると消える）
+static JOURNAL: Mutex<Journal> = Mutex::new(Journal {
+    undo: Vec::new(),
+    redo: Vec::new(),
+});
+
+/// How many changes can be undone.
+const MAX_UNDO: usize = 50;
+
+/// Record a change that was just saved as `after`. Clears the redo stack.
+pub fn record(kind: ChangeKind, before: Snapshot, after: &AppData, trash: TrashDelta) {
+    let mut journal = JOURNAL.lock().unwrap();
+    journal.redo.clear();
+    journal.undo.push(Change {
+        kind,
+        before,
+        after: Snapshot::of(after),
+        trash,
+        revision: after.revision,
+    });
+    if journal.undo.len() > MAX_UNDO {
+        let excess = journal.undo.len() - MAX_UNDO;
+        journal.undo.drain(..excess);
+    }
+}
+
+pub fn status() -> UndoStatus {
+    let journal = JOURNAL.lock().unwrap();
+    UndoStatus {
+        undo: journal.undo.last().map(|change| change.kind),
+        redo: journal.redo.last().map(|change| change.kind),
+    }
+}
+
+/// Forget every change, e.g. after switching to another profile, whose data
+/// the recorded snapshots do not belong to.
+pub fn clear() {
+    let mut journal = JOURNAL.lock().unwrap();
+    journal.undo.clear();
+    journal.redo.clear();
+}
+
+/// 直前の変更を取り消す
+pub fn undo() -> Result<AppData, String> {
+    step(Direction::Undo)
+}
+
+/// 取り消した変更をやり直す
+pub fn redo() -> Result<AppData, String> {
+    step(Direction::Redo)
+}
+
+enum Direction {
+    Undo,
+    Redo,
+}
+
+fn step(direction: Direction) -> Result<AppData, String> {
+    let mut journal = JOURNAL.lock().unwrap();
+    let Journal { undo, redo } = &mut *journal;
+    let (from, to) = match direction {
+        Direction::Undo => (undo, redo),
+        Direction::Redo => (redo, undo),
+    };
+    let Some(mut change) = from.pop() else {
+        return Err(match direction {
+            Direction::Undo => "Nothing to undo".to_string(),
+            Direction::Redo => "Nothing to redo".to_string(),
+        });
+    };
+
+    let target = match direction {
+        Direction::Undo => &change.before,
+        Direction::Redo => &change.after,
+    };
+    let result = storage::update_app_data(Some(change.revision), |data| {
+        target.restore(data);
+        Ok(())
+    });
+
+    match result {
+        Ok((data, ())) => {
+            let trash = match direction {
+                Direction::Undo => change.trash.revert(),
+                Direction::Redo => change.trash.apply(),
+            };
+            if let Err(e) = trash {
+                eprintln!("Failed to update trash: {}", e);
+            }
+            change.revision = data.revision;
+            to.push(change);
+            Ok(data)
+        }
+        Err(e) if e.starts_with(storage::CONFLICT_ERROR_PREFIX) => {
+            // data.json は記録の外で変更された（復元・外部編集など）。
+            // 古い状態に戻すと他の変更を消してしまうので履歴ごと捨てる
+            from.clear();
+            to.clear();
+            Err(e)
+        }
+        Err(e) => {
+            from.push(change);
+            Err(e)
+        }
+    }
+}
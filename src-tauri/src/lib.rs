@@ -1,9 +1,41 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use std::process::Command;
 use sysinfo::{ProcessesToUpdate, System};
 
+mod bundle;
+mod cli;
+mod crud;
+mod data_dir;
+mod data_format;
+mod executor;
+#[cfg(test)]
+mod fixtures;
+mod history;
+mod instance;
+mod journal;
+mod link;
+mod migrations;
+mod profiles;
+mod share_code;
+mod storage;
+mod trash;
+mod validation;
+mod watcher;
+
+use bundle::{Bundle, ExportSelection, ImportOptions, ImportReport};
+use crud::{GroupInput, ShortcutInput};
+use data_dir::DataLocation;
+use data_format::DataFormat;
+use executor::{ActionResult, RunInfo, RunPlan, RunRegistry, RunReport};
+use history::{HistoryEntry, HistoryQuery, HistoryRetention, PruneRequest, RunSource};
+use journal::{ChangeKind, UndoStatus};
+use profiles::ProfileInfo;
+use share_code::SharedShortcut;
+use storage::{load_app_data, BackupInfo, QuarantinedFile, DEFAULT_GROUP_ID};
+use trash::{TrashDelta, TrashEntry, TrashedItem};
+use validation::ValidationReport;
+
 #[cfg(windows)]
 use base64::Engine;
 #[cfg(windows)]
@@ -51,6 +83,62 @@ pub enum Action {
     },
 }
 
+impl Action {
+    /// The serialized `type` tag of this action.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Action::Launch { .. } => "launch",
+            Action::Kill { .. } => "kill",
+            Action::OpenFolder { .. } => "open_folder",
+            Action::OpenUrl { .. } => "open_url",
+            Action::Delay { .. } => "delay",
+        }
+    }
+}
+
+/// アクション失敗時の振る舞い
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(
+    tag = "mode",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum ErrorPolicy {
+    /// Stop the chain and skip the remaining actions.
+    Abort,
+    /// Record the failure and run the next action.
+    #[default]
+    Continue,
+    /// Run the action again up to `retries` more times, waiting `delay_ms`
+    /// (multiplied by `backoff` after every attempt). If every attempt fails
+    /// the chain is aborted.
+    Retry {
+        retries: u32,
+        delay_ms: u64,
+        #[serde(default = "default_backoff")]
+        backoff: f64,
+    },
+}
+
+fn default_backoff() -> f64 {
+    2.0
+}
+
+/// An action as stored in a shortcut, together with its per-action options.
+/// The options are flattened next to the action's `type` tag in JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionStep {
+    #[serde(flatten)]
+    pub action: Action,
+    /// Overrides `Shortcut::on_error` for this action.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_error: Option<ErrorPolicy>,
+    /// Fails the action with a timeout error if a single attempt takes longer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Shortcut {
@@ -58,7 +146,13 @@ pub struct Shortcut {
     pub name: String,
     pub icon: String,
     pub group_id: String,
-    pub actions: Vec<Action>,
+    pub actions: Vec<ActionStep>,
+    /// Default error policy for actions without their own `on_error`.
+    #[serde(default)]
+    pub on_error: ErrorPolicy,
+    /// `advanced-shortcut://run/...` のリンクから確認なしで実行してよいか
+    #[serde(default)]
+    pub link_runnable: bool,
     pub order: i32,
     pub created_at: String,
     pub updated_at: String,
@@ -77,10 +171,68 @@ pub struct Group {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppData {
+    /// Incremented on every save. Saves based on an older revision are
+    /// rejected so concurrent writers cannot overwrite each other.
+    #[serde(default)]
+    pub revision: u64,
     pub shortcuts: Vec<Shortcut>,
     pub groups: Vec<Group>,
 }
 
+/// 保存コマンドの結果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveResult {
+    /// Revision of the data now on disk; pass it as `expectedRevision` next time.
+    pub revision: u64,
+    /// Warnings found while validating (errors reject the save instead).
+    pub validation: ValidationReport,
+}
+
+/// 個別の作成・更新・削除・移動コマンドの結果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MutationResult {
+    /// The saved data, including the new revision and normalized order.
+    pub data: AppData,
+    /// Id of the shortcut or group that was created or restored.
+    pub id: Option<String>,
+    pub validation: ValidationReport,
+    pub undo: UndoStatus,
+}
+
+fn mutate(
+    change: ChangeKind,
+    expected_revision: Option<u64>,
+    update: impl FnOnce(&mut AppData, &mut TrashDelta) -> Result<Option<String>, String>,
+) -> Result<MutationResult, String> {
+    let (data, id, validation) = mutate_validated_app_data(change, expected_revision, update)?;
+    Ok(MutationResult {
+        data,
+        id,
+        validation,
+        undo: journal::status(),
+    })
+}
+
+/// バンドルを書き出した結果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub path: String,
+    pub shortcut_count: usize,
+    pub group_count: usize,
+}
+
+/// バンドルを取り込んだ結果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    #[serde(flatten)]
+    pub mutation: MutationResult,
+    pub report: ImportReport,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ProcessInfo {
     pub pid: u32,
@@ -102,415 +254,163 @@ pub struct WindowInfo {
 }
 
 // ========================================
-// CLI Shortcut Execution
+// Bundle Helpers
 // ========================================
 
-#[derive(Debug, Default, Clone, Serialize)]
-struct CliShortcutRequest {
-    shortcut_id: String,
+fn export_bundle_to_file(
+    path: &std::path::Path,
+    selection: &ExportSelection,
+) -> Result<ExportSummary, String> {
+    let data = load_app_data()?;
+    let bundle = bundle::export(&data, selection)?;
+    let content = bundle::to_string(&bundle, bundle_format(path))?;
+    storage::write_atomic(path, content.as_bytes())
+        .map_err(|e| format!("Failed to write bundle: {}", e))?;
+    Ok(ExportSummary {
+        path: path.to_string_lossy().to_string(),
+        shortcut_count: bundle.shortcuts.len(),
+        group_count: bundle.groups.len(),
+    })
 }
 
-fn parse_cli_shortcut_request() -> Option<CliShortcutRequest> {
-    let mut request = CliShortcutRequest::default();
-
-    let mut args = std::env::args().skip(1);
-    while let Some(arg) = args.next() {
-        match arg.as_str() {
-            "--execute-shortcut" => {
-                if let Some(id) = args.next() {
-                    request.shortcut_id = id;
-                }
-            }
-            _ => {}
-        }
-    }
-
-    if request.shortcut_id.is_empty() {
-        None
-    } else {
-        Some(request)
-    }
+fn read_bundle(path: &std::path::Path) -> Result<Bundle, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read bundle: {}", e))?;
+    bundle::parse(&content, bundle_format(path))
 }
 
-/// CLI引数からショートカットを直接実行する（フロントエンドを介さない）
-fn execute_shortcut_from_cli(request: &CliShortcutRequest) -> Result<(), String> {
-    // データをロードしてショートカットを検索
-    let app_data = load_app_data();
-    let shortcut = app_data
-        .shortcuts
-        .iter()
-        .find(|s| s.id == request.shortcut_id)
-        .ok_or_else(|| format!("Shortcut not found: {}", request.shortcut_id))?
-        .clone();
-
-    // 同期的にショートカットのアクションを実行
-    for action in shortcut.actions {
-        if let Err(e) = execute_action_sync(&action) {
-            eprintln!("Action error: {}", e);
-        }
-    }
-
-    Ok(())
+/// バンドルの書式は拡張子で選ぶ（不明な拡張子は JSON）
+fn bundle_format(path: &std::path::Path) -> DataFormat {
+    DataFormat::from_path(path).unwrap_or_default()
 }
 
-/// アクションを同期的に実行する（CLI実行用）
-fn execute_action_sync(action: &Action) -> Result<String, String> {
-    match action {
-        Action::Launch {
-            path,
-            args,
-            window_config,
-        } => {
-            let exe_name = std::path::Path::new(&path)
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default();
-
-            #[cfg(windows)]
-            {
-                let existing_windows = window_control::find_windows_by_process_name(&exe_name);
-
-                if !existing_windows.is_empty() {
-                    if let Some(config) = window_config {
-                        for hwnd in existing_windows {
-                            if let Some((cur_x, cur_y, cur_w, cur_h)) =
-                                window_control::get_window_rect(hwnd)
-                            {
-                                let x = config.x.unwrap_or(cur_x);
-                                let y = config.y.unwrap_or(cur_y);
-                                let w = config.width.unwrap_or(cur_w);
-                                let h = config.height.unwrap_or(cur_h);
-                                let _ = window_control::set_window_position(hwnd, x, y, w, h);
-                            }
-                        }
-                    }
-                    return Ok(format!("Adjusted window for already running: {}", exe_name));
-                }
-            }
-
-            let mut cmd = Command::new(&path);
-            if let Some(arguments) = args {
-                cmd.args(arguments);
-            }
-
-            let child = cmd
-                .spawn()
-                .map_err(|e| format!("Failed to launch {}: {}", path, e))?;
-
-            #[cfg(windows)]
-            if let Some(config) = window_config {
-                let pid = child.id();
-                std::thread::sleep(std::time::Duration::from_millis(1000));
-
-                for _ in 0..10 {
-                    if let Some(hwnd) = window_control::find_window_by_pid(pid) {
-                        let x = config.x.unwrap_or(0);
-                        let y = config.y.unwrap_or(0);
-                        let w = config.width.unwrap_or(800);
-                        let h = config.height.unwrap_or(600);
-                        let _ = window_control::set_window_position(hwnd, x, y, w, h);
-                        break;
-                    }
-                    std::thread::sleep(std::time::Duration::from_millis(200));
-                }
-            }
-
-            Ok(format!("Launched: {}", path))
-        }
-
-        Action::Kill { process_name } => {
-            let mut sys = System::new_all();
-            sys.refresh_processes(ProcessesToUpdate::All, true);
-
-            let mut killed_count = 0;
-            for (_pid, process) in sys.processes() {
-                if process.name().to_string_lossy().to_lowercase() == process_name.to_lowercase() {
-                    process.kill();
-                    killed_count += 1;
-                }
-            }
-
-            if killed_count > 0 {
-                Ok(format!(
-                    "Killed {} instance(s) of {}",
-                    killed_count, process_name
-                ))
-            } else {
-                Err(format!("Process not found: {}", process_name))
-            }
-        }
-
-        Action::OpenFolder { path, window_config } => {
-            #[cfg(windows)]
-            let before_windows: std::collections::HashSet<isize> = if window_config.is_some() {
-                window_control::get_explorer_windows()
-                    .iter()
-                    .map(|(hwnd, _)| hwnd.0 as isize)
-                    .collect()
-            } else {
-                std::collections::HashSet::new()
-            };
-
-            open::that(&path).map_err(|e| format!("Failed to open folder {}: {}", path, e))?;
-
-            #[cfg(windows)]
-            if let Some(config) = window_config {
-                std::thread::sleep(std::time::Duration::from_millis(800));
-
-                let after_windows = window_control::get_explorer_windows();
-                
-                let new_window = after_windows.iter().find(|(hwnd, _)| {
-                    !before_windows.contains(&(hwnd.0 as isize))
-                });
-
-                let target_hwnd = if let Some((hwnd, _)) = new_window {
-                    Some(*hwnd)
-                } else {
-                    let folder_name = std::path::Path::new(&path)
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_default();
-                    
-                    after_windows.iter()
-                        .find(|(_, title)| title.contains(&folder_name))
-                        .map(|(hwnd, _)| *hwnd)
-                };
-
-                if let Some(hwnd) = target_hwnd {
-                    if let Some((cur_x, cur_y, cur_w, cur_h)) = window_control::get_window_rect(hwnd) {
-                        let x = config.x.unwrap_or(cur_x);
-                        let y = config.y.unwrap_or(cur_y);
-                        let w = config.width.unwrap_or(cur_w);
-                        let h = config.height.unwrap_or(cur_h);
-                        let _ = window_control::set_window_position(hwnd, x, y, w, h);
-                    }
-                }
-            }
-
-            Ok(format!("Opened folder: {}", path))
-        }
-
-        Action::OpenUrl { url, window_config } => {
-            #[cfg(windows)]
-            let before_windows: std::collections::HashSet<isize> = if window_config.is_some() {
-                let mut windows = std::collections::HashSet::new();
-                for browser in ["chrome.exe", "msedge.exe", "firefox.exe", "brave.exe", "opera.exe"] {
-                    for (hwnd, _) in window_control::get_windows_by_process_name(browser) {
-                        windows.insert(hwnd.0 as isize);
-                    }
-                }
-                windows
-            } else {
-                std::collections::HashSet::new()
-            };
-
-            #[cfg(windows)]
-            {
-                use winreg::enums::*;
-                use winreg::RegKey;
-                
-                let mut opened = false;
-                
-                fn get_browser_path(prog_id: &str) -> Option<String> {
-                    let hkcr = RegKey::predef(HKEY_CLASSES_ROOT);
-                    let command_key = format!(r"{}\shell\open\command", prog_id);
-                    if let Ok(key) = hkcr.open_subkey(&command_key) {
-                        if let Ok(cmd) = key.get_value::<String, _>("") {
-                            let cmd = cmd.trim();
-                            if cmd.starts_with('"') {
-                                if let Some(end) = cmd[1..].find('"') {
-                                    return Some(cmd[1..end+1].to_string());
-                                }
-                            } else if let Some(end) = cmd.find(' ') {
-                                return Some(cmd[..end].to_string());
-                            } else {
-                                return Some(cmd.to_string());
-                            }
-                        }
-                    }
-                    None
-                }
-                
-                if let Ok(hkcu) = RegKey::predef(HKEY_CURRENT_USER)
-                    .open_subkey(r"Software\Microsoft\Windows\Shell\Associations\UrlAssociations\http\UserChoice")
-                {
-                    if let Ok(prog_id) = hkcu.get_value::<String, _>("ProgId") {
-                        if let Some(browser_path) = get_browser_path(&prog_id) {
-                            let prog_id_lower = prog_id.to_lowercase();
-                            
-                            let new_window_arg = if prog_id_lower.contains("firefox") {
-                                "-new-window"
-                            } else {
-                                "--new-window"
-                            };
-                            
-                            if Command::new(&browser_path)
-                                .args([new_window_arg, &url])
-                                .spawn()
-                                .is_ok()
-                            {
-                                opened = true;
-                            }
-                        }
-                    }
-                }
-                
-                if !opened {
-                    let browser_paths = [
-                        (r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe", "--new-window"),
-                        (r"C:\Program Files\Microsoft\Edge\Application\msedge.exe", "--new-window"),
-                        (r"C:\Program Files\Google\Chrome\Application\chrome.exe", "--new-window"),
-                        (r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe", "--new-window"),
-                        (r"C:\Program Files\Mozilla Firefox\firefox.exe", "-new-window"),
-                        (r"C:\Program Files\BraveSoftware\Brave-Browser\Application\brave.exe", "--new-window"),
-                    ];
-                    
-                    for (path, arg) in browser_paths {
-                        if std::path::Path::new(path).exists() {
-                            if Command::new(path)
-                                .args([arg, &url])
-                                .spawn()
-                                .is_ok()
-                            {
-                                opened = true;
-                                break;
-                            }
-                        }
-                    }
-                }
-                
-                if !opened {
-                    let _ = Command::new("cmd")
-                        .args(["/c", "start", "", &url])
-                        .spawn()
-                        .map_err(|e| format!("Failed to open URL {}: {}", url, e))?;
-                }
-            }
-
-            #[cfg(not(windows))]
-            {
-                open::that(&url).map_err(|e| format!("Failed to open URL {}: {}", url, e))?;
-            }
-
-            #[cfg(windows)]
-            if let Some(config) = window_config {
-                std::thread::sleep(std::time::Duration::from_millis(1500));
-
-                let mut target_hwnd: Option<windows::Win32::Foundation::HWND> = None;
-                
-                unsafe {
-                    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
-                    let fg_hwnd = GetForegroundWindow();
-                    if !fg_hwnd.is_invalid() {
-                        for browser in ["chrome.exe", "msedge.exe", "firefox.exe", "brave.exe", "opera.exe"] {
-                            let browser_windows = window_control::get_windows_by_process_name(browser);
-                            for (hwnd, _) in &browser_windows {
-                                if hwnd.0 == fg_hwnd.0 {
-                                    target_hwnd = Some(*hwnd);
-                                    break;
-                                }
-                            }
-                            if target_hwnd.is_some() {
-                                break;
-                            }
-                        }
-                    }
-                }
-                
-                if target_hwnd.is_none() {
-                    for browser in ["chrome.exe", "msedge.exe", "firefox.exe", "brave.exe", "opera.exe"] {
-                        let after_windows = window_control::get_windows_by_process_name(browser);
-                        for (hwnd, _) in after_windows {
-                            if !before_windows.contains(&(hwnd.0 as isize)) {
-                                target_hwnd = Some(hwnd);
-                                break;
-                            }
-                        }
-                        if target_hwnd.is_some() {
-                            break;
-                        }
-                    }
-                }
-
-                if let Some(hwnd) = target_hwnd {
-                    if let Some((cur_x, cur_y, cur_w, cur_h)) = window_control::get_window_rect(hwnd) {
-                        let x = config.x.unwrap_or(cur_x);
-                        let y = config.y.unwrap_or(cur_y);
-                        let w = config.width.unwrap_or(cur_w);
-                        let h = config.height.unwrap_or(cur_h);
-                        let _ = window_control::set_window_position(hwnd, x, y, w, h);
-                    }
-                }
-            }
-
-            Ok(format!("Opened URL: {}", url))
-        }
+fn import_bundle_data(
+    bundle: Bundle,
+    options: &ImportOptions,
+    expected_revision: Option<u64>,
+) -> Result<(MutationResult, ImportReport), String> {
+    let (data, report, validation) = mutate_validated_app_data(
+        ChangeKind::ImportBundle,
+        expected_revision,
+        |data, trash| bundle::import(data, bundle, options, trash),
+    )?;
+    let mutation = MutationResult {
+        data,
+        id: None,
+        validation,
+        undo: journal::status(),
+    };
+    Ok((mutation, report))
+}
 
-        Action::Delay { ms } => {
-            std::thread::sleep(std::time::Duration::from_millis(*ms));
-            Ok(format!("Delayed for {}ms", ms))
-        }
-    }
+/// 保存せずに、取り込んだ場合の結果だけを返す
+fn preview_import(bundle: Bundle, options: &ImportOptions) -> Result<ImportReport, String> {
+    let mut data = load_app_data()?;
+    bundle::import(&mut data, bundle, options, &mut TrashDelta::default())
 }
 
-#[tauri::command]
-fn exit_app(app_handle: tauri::AppHandle, code: i32) {
-    app_handle.exit(code);
+/// 共有コードのショートカットを `group_id`（省略時はデフォルトグループ）の末尾に追加する
+fn import_share_code_data(
+    code: &str,
+    group_id: Option<String>,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    let shared = share_code::decode(code)?;
+    let group_id = group_id.unwrap_or_else(|| DEFAULT_GROUP_ID.to_string());
+    mutate(ChangeKind::CreateShortcut, expected_revision, |data, _| {
+        crud::create_shortcut(data, shared.into_input(group_id)).map(Some)
+    })
 }
 
 // ========================================
 // Utility Functions
 // ========================================
 
-fn get_data_path() -> PathBuf {
-    let data_dir = dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("advanced-shortcut");
+/// 使用中のプロファイルのフォルダ。データフォルダ自体は `--data-dir`・
+/// 環境変数・ポータブルモードで変更できる
+fn get_data_dir() -> PathBuf {
+    let data_dir = profiles::profile_dir(&profiles::active());
 
     // Create directory if it doesn't exist
     if !data_dir.exists() {
         fs::create_dir_all(&data_dir).ok();
     }
 
-    data_dir.join("data.json")
+    data_dir
 }
 
-fn load_app_data() -> AppData {
-    let path = get_data_path();
+/// data.json・data.yaml・data.yml・data.toml のうち最初に見つかったもの。
+/// どれも無ければ data.json
+fn get_data_path() -> PathBuf {
+    let data_dir = get_data_dir();
+    storage::DATA_FILE_NAMES
+        .iter()
+        .map(|name| data_dir.join(name))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| data_dir.join(storage::DATA_FILE_NAMES[0]))
+}
 
-    if let Ok(content) = fs::read_to_string(&path) {
-        serde_json::from_str(&content).unwrap_or_else(|_| default_app_data())
-    } else {
-        default_app_data()
+/// 実行履歴の保存に失敗しても実行結果自体は返す
+fn record_history(shortcut: &Shortcut, report: &RunReport, source: RunSource) {
+    if let Err(e) = history::record_run(shortcut, report, source) {
+        eprintln!("Failed to record execution history: {}", e);
     }
 }
 
-fn save_app_data(data: &AppData) -> Result<(), String> {
-    let path = get_data_path();
-    let json = serde_json::to_string_pretty(data)
-        .map_err(|e| format!("Failed to serialize data: {}", e))?;
-    fs::write(&path, json).map_err(|e| format!("Failed to write data: {}", e))?;
-    Ok(())
+/// 検証してから保存する。エラーがあれば保存せずに拒否し、警告だけなら保存して返す
+///
+/// `update` は data.json のロックを保持したまま最新のデータに適用される。
+fn save_validated_app_data(
+    expected_revision: Option<u64>,
+    update: impl FnOnce(&mut AppData),
+) -> Result<SaveResult, String> {
+    let (data, (), validation) =
+        mutate_validated_app_data(ChangeKind::Save, expected_revision, |data, _| {
+            update(data);
+            Ok(())
+        })?;
+    Ok(SaveResult {
+        revision: data.revision,
+        validation,
+    })
 }
 
-fn default_app_data() -> AppData {
-    AppData {
-        shortcuts: vec![],
-        groups: vec![Group {
-            id: "default".to_string(),
-            name: "デフォルト".to_string(),
-            color: "#22d3ee".to_string(),
-            icon: "folder".to_string(),
-            order: 0,
-            is_expanded: true,
-        }],
+/// [`save_validated_app_data`] for updates that can fail or produce a value.
+/// Returns the saved data, the update's value and the validation warnings.
+///
+/// 保存した変更は元に戻せるよう記録する。`update` が削除・復元でゴミ箱を
+/// 変える場合は `TrashDelta` に書き、保存に成功したときだけ反映される。
+fn mutate_validated_app_data<T>(
+    change: ChangeKind,
+    expected_revision: Option<u64>,
+    update: impl FnOnce(&mut AppData, &mut TrashDelta) -> Result<T, String>,
+) -> Result<(AppData, T, ValidationReport), String> {
+    let mut trash = TrashDelta::default();
+    let (data, (value, report, before)) = storage::update_app_data(expected_revision, |data| {
+        let before = journal::Snapshot::of(data);
+        let value = update(data, &mut trash)?;
+        let report = validation::validate(data);
+        if report.has_errors() {
+            return Err(format!("Invalid app data: {}", report.error_summary()));
+        }
+        Ok((value, report, before))
+    })?;
+
+    if let Err(e) = trash.apply() {
+        eprintln!("Failed to update trash: {}", e);
     }
+    journal::record(change, before, &data, trash);
+    Ok((data, value, report))
 }
 
 // ========================================
 // Tauri Commands
 // ========================================
 
+#[tauri::command]
+fn exit_app(app_handle: tauri::AppHandle, code: i32) {
+    app_handle.exit(code);
+}
+
 #[cfg(windows)]
 mod window_control {
     use std::sync::atomic::{AtomicIsize, Ordering};
@@ -582,10 +482,10 @@ mod window_control {
 
     pub fn find_all_windows_by_pid(target_pid: u32) -> Vec<HWND> {
         use std::sync::Mutex;
-        
+
         static RESULTS: std::sync::OnceLock<Mutex<Vec<isize>>> = std::sync::OnceLock::new();
         static TARGET_PID: AtomicIsize = AtomicIsize::new(0);
-        
+
         let results_lock = RESULTS.get_or_init(|| Mutex::new(Vec::new()));
         {
             let mut results = results_lock.lock().unwrap();
@@ -610,22 +510,26 @@ mod window_control {
         }
 
         unsafe {
-            let _ = EnumWindows(Some(enum_callback), LPARAM(results_lock as *const _ as isize));
+            let _ = EnumWindows(
+                Some(enum_callback),
+                LPARAM(results_lock as *const _ as isize),
+            );
         }
 
         let results = results_lock.lock().unwrap();
-        results.iter().map(|&h| HWND(h as *mut std::ffi::c_void)).collect()
+        results
+            .iter()
+            .map(|&h| HWND(h as *mut std::ffi::c_void))
+            .collect()
     }
 
     // Get all visible explorer windows with their hwnds
     pub fn get_explorer_windows() -> Vec<(HWND, String)> {
-        use windows::Win32::UI::WindowsAndMessaging::{
-            GetWindowTextLengthW, GetWindowTextW,
-        };
-        
+        use windows::Win32::UI::WindowsAndMessaging::{GetWindowTextLengthW, GetWindowTextW};
+
         let windows = find_windows_by_process_name("explorer.exe");
         let mut result = Vec::new();
-        
+
         for hwnd in windows {
             unsafe {
                 let title_len = GetWindowTextLengthW(hwnd);
@@ -637,19 +541,17 @@ mod window_control {
                 }
             }
         }
-        
+
         result
     }
 
     // Get all visible windows by process name (for browser windows etc.)
     pub fn get_windows_by_process_name(process_name: &str) -> Vec<(HWND, String)> {
-        use windows::Win32::UI::WindowsAndMessaging::{
-            GetWindowTextLengthW, GetWindowTextW,
-        };
-        
+        use windows::Win32::UI::WindowsAndMessaging::{GetWindowTextLengthW, GetWindowTextW};
+
         let windows = find_windows_by_process_name(process_name);
         let mut result = Vec::new();
-        
+
         for hwnd in windows {
             unsafe {
                 let title_len = GetWindowTextLengthW(hwnd);
@@ -661,7 +563,7 @@ mod window_control {
                 }
             }
         }
-        
+
         result
     }
 
@@ -697,367 +599,346 @@ mod window_control {
 }
 
 #[tauri::command]
-async fn execute_action(action: Action) -> Result<String, String> {
-    match action {
-        Action::Launch {
-            path,
-            args,
-            window_config,
-        } => {
-            // Check if process is already running
-            let exe_name = std::path::Path::new(&path)
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default();
+async fn execute_action(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, RunRegistry>,
+    action: Action,
+    timeout_ms: Option<u64>,
+) -> Result<ActionResult, String> {
+    let registry = registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let run = registry.begin("");
+        let runtime = executor::GuiRuntime::new(app_handle);
+        executor::execute_action(&action, timeout_ms, &run, &runtime)
+    })
+    .await
+    .map_err(|e| format!("Execution task failed: {}", e))
+}
 
-            #[cfg(windows)]
-            {
-                let existing_windows = window_control::find_windows_by_process_name(&exe_name);
-
-                if !existing_windows.is_empty() {
-                    // App already running, just adjust window
-                    if let Some(config) = window_config {
-                        for hwnd in existing_windows {
-                            if let Some((cur_x, cur_y, cur_w, cur_h)) =
-                                window_control::get_window_rect(hwnd)
-                            {
-                                let x = config.x.unwrap_or(cur_x);
-                                let y = config.y.unwrap_or(cur_y);
-                                let w = config.width.unwrap_or(cur_w);
-                                let h = config.height.unwrap_or(cur_h);
-                                let _ = window_control::set_window_position(hwnd, x, y, w, h);
-                            }
-                        }
-                    }
-                    return Ok(format!("Adjusted window for already running: {}", exe_name));
-                }
-            }
+#[tauri::command]
+async fn execute_shortcut(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, RunRegistry>,
+    shortcut: Shortcut,
+) -> Result<RunReport, String> {
+    let registry = registry.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let run = registry.begin(&shortcut.id);
+        let runtime = executor::GuiRuntime::new(app_handle);
+        let report = executor::execute_shortcut(&shortcut, &run, &runtime);
+        record_history(&shortcut, &report, RunSource::Gui);
+        report
+    })
+    .await
+    .map_err(|e| format!("Execution task failed: {}", e))
+}
 
-            // Launch new process
-            let mut cmd = Command::new(&path);
-            if let Some(arguments) = args {
-                cmd.args(arguments);
-            }
+/// ショートカットをドライランし、実行計画を返す
+#[tauri::command]
+async fn plan_shortcut(shortcut: Shortcut) -> Result<RunPlan, String> {
+    tauri::async_runtime::spawn_blocking(move || executor::plan_shortcut(&shortcut))
+        .await
+        .map_err(|e| format!("Planning task failed: {}", e))
+}
 
-            let child = cmd
-                .spawn()
-                .map_err(|e| format!("Failed to launch {}: {}", path, e))?;
+#[tauri::command]
+fn cancel_shortcut_run(
+    registry: tauri::State<'_, RunRegistry>,
+    run_id: String,
+) -> Result<(), String> {
+    if registry.cancel(&run_id) {
+        Ok(())
+    } else {
+        Err(format!("Run not found: {}", run_id))
+    }
+}
 
-            // If window config is set, wait a bit and then set window position
-            #[cfg(windows)]
-            if let Some(config) = window_config {
-                let pid = child.id();
-                tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-
-                // Try to find and resize the window
-                for _ in 0..10 {
-                    if let Some(hwnd) = window_control::find_window_by_pid(pid) {
-                        let x = config.x.unwrap_or(0);
-                        let y = config.y.unwrap_or(0);
-                        let w = config.width.unwrap_or(800);
-                        let h = config.height.unwrap_or(600);
-                        let _ = window_control::set_window_position(hwnd, x, y, w, h);
-                        break;
-                    }
-                    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-                }
-            }
+#[tauri::command]
+fn get_active_runs(registry: tauri::State<'_, RunRegistry>) -> Vec<RunInfo> {
+    registry.active()
+}
 
-            Ok(format!("Launched: {}", path))
-        }
+#[tauri::command]
+fn get_execution_history(query: Option<HistoryQuery>) -> Result<Vec<HistoryEntry>, String> {
+    history::query(&query.unwrap_or_default())
+}
 
-        Action::Kill { process_name } => {
-            let mut sys = System::new_all();
-            sys.refresh_processes(ProcessesToUpdate::All, true);
+#[tauri::command]
+fn prune_execution_history(request: Option<PruneRequest>) -> Result<usize, String> {
+    history::prune(&request.unwrap_or_default())
+}
 
-            let mut killed_count = 0;
-            for (_pid, process) in sys.processes() {
-                if process.name().to_string_lossy().to_lowercase() == process_name.to_lowercase() {
-                    process.kill();
-                    killed_count += 1;
-                }
-            }
+#[tauri::command]
+fn get_history_retention() -> Result<HistoryRetention, String> {
+    history::get_retention()
+}
 
-            if killed_count > 0 {
-                Ok(format!(
-                    "Killed {} instance(s) of {}",
-                    killed_count, process_name
-                ))
-            } else {
-                Err(format!("Process not found: {}", process_name))
-            }
-        }
+#[tauri::command]
+fn set_history_retention(retention: HistoryRetention) -> Result<(), String> {
+    history::set_retention(retention)
+}
 
-        Action::OpenFolder { path, window_config } => {
-            #[cfg(windows)]
-            let before_windows: std::collections::HashSet<isize> = if window_config.is_some() {
-                window_control::get_explorer_windows()
-                    .iter()
-                    .map(|(hwnd, _)| hwnd.0 as isize)
-                    .collect()
-            } else {
-                std::collections::HashSet::new()
-            };
+#[tauri::command]
+fn get_shortcuts() -> Result<Vec<Shortcut>, String> {
+    let data = load_app_data()?;
+    Ok(data.shortcuts)
+}
 
-            open::that(&path).map_err(|e| format!("Failed to open folder {}: {}", path, e))?;
+#[tauri::command]
+fn save_shortcuts(
+    shortcuts: Vec<Shortcut>,
+    expected_revision: Option<u64>,
+) -> Result<SaveResult, String> {
+    save_validated_app_data(expected_revision, |data| data.shortcuts = shortcuts)
+}
 
-            // If window config is set, wait and then set window position
-            #[cfg(windows)]
-            if let Some(config) = window_config {
-                // Wait for the window to open
-                tokio::time::sleep(tokio::time::Duration::from_millis(800)).await;
-
-                // Find the new explorer window by comparing before and after
-                let after_windows = window_control::get_explorer_windows();
-                
-                // Find windows that weren't there before
-                let new_window = after_windows.iter().find(|(hwnd, _)| {
-                    !before_windows.contains(&(hwnd.0 as isize))
-                });
+#[tauri::command]
+fn get_groups() -> Result<Vec<Group>, String> {
+    let data = load_app_data()?;
+    Ok(data.groups)
+}
 
-                // If we found a new window, use it; otherwise try to find by folder name
-                let target_hwnd = if let Some((hwnd, _)) = new_window {
-                    Some(*hwnd)
-                } else {
-                    // Fallback: find window with matching folder name in title
-                    let folder_name = std::path::Path::new(&path)
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_default();
-                    
-                    after_windows.iter()
-                        .find(|(_, title)| title.contains(&folder_name))
-                        .map(|(hwnd, _)| *hwnd)
-                };
-
-                if let Some(hwnd) = target_hwnd {
-                    if let Some((cur_x, cur_y, cur_w, cur_h)) = window_control::get_window_rect(hwnd) {
-                        let x = config.x.unwrap_or(cur_x);
-                        let y = config.y.unwrap_or(cur_y);
-                        let w = config.width.unwrap_or(cur_w);
-                        let h = config.height.unwrap_or(cur_h);
-                        let _ = window_control::set_window_position(hwnd, x, y, w, h);
-                    }
-                }
-            }
+#[tauri::command]
+fn save_groups(groups: Vec<Group>, expected_revision: Option<u64>) -> Result<SaveResult, String> {
+    save_validated_app_data(expected_revision, |data| data.groups = groups)
+}
 
-            Ok(format!("Opened folder: {}", path))
-        }
+#[tauri::command]
+fn create_shortcut(
+    shortcut: ShortcutInput,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    mutate(ChangeKind::CreateShortcut, expected_revision, |data, _| {
+        crud::create_shortcut(data, shortcut).map(Some)
+    })
+}
 
-        Action::OpenUrl { url, window_config } => {
-            #[cfg(windows)]
-            let before_windows: std::collections::HashSet<isize> = if window_config.is_some() {
-                // Get browser windows before opening
-                let mut windows = std::collections::HashSet::new();
-                for browser in ["chrome.exe", "msedge.exe", "firefox.exe", "brave.exe", "opera.exe"] {
-                    for (hwnd, _) in window_control::get_windows_by_process_name(browser) {
-                        windows.insert(hwnd.0 as isize);
-                    }
-                }
-                windows
-            } else {
-                std::collections::HashSet::new()
-            };
+#[tauri::command]
+fn update_shortcut(
+    id: String,
+    shortcut: ShortcutInput,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    mutate(ChangeKind::UpdateShortcut, expected_revision, |data, _| {
+        crud::update_shortcut(data, &id, shortcut).map(|_| None)
+    })
+}
 
-            // Open URL in new browser window
-            #[cfg(windows)]
-            {
-                use winreg::enums::*;
-                use winreg::RegKey;
-                
-                let mut opened = false;
-                
-                // Helper function to get browser path from registry
-                fn get_browser_path(prog_id: &str) -> Option<String> {
-                    let hkcr = RegKey::predef(HKEY_CLASSES_ROOT);
-                    let command_key = format!(r"{}\shell\open\command", prog_id);
-                    if let Ok(key) = hkcr.open_subkey(&command_key) {
-                        if let Ok(cmd) = key.get_value::<String, _>("") {
-                            // Extract path from command like: "C:\...\chrome.exe" --args
-                            let cmd = cmd.trim();
-                            if cmd.starts_with('"') {
-                                if let Some(end) = cmd[1..].find('"') {
-                                    return Some(cmd[1..end+1].to_string());
-                                }
-                            } else if let Some(end) = cmd.find(' ') {
-                                return Some(cmd[..end].to_string());
-                            } else {
-                                return Some(cmd.to_string());
-                            }
-                        }
-                    }
-                    None
-                }
-                
-                // Try to get default browser from registry
-                if let Ok(hkcu) = RegKey::predef(HKEY_CURRENT_USER)
-                    .open_subkey(r"Software\Microsoft\Windows\Shell\Associations\UrlAssociations\http\UserChoice")
-                {
-                    if let Ok(prog_id) = hkcu.get_value::<String, _>("ProgId") {
-                        if let Some(browser_path) = get_browser_path(&prog_id) {
-                            let prog_id_lower = prog_id.to_lowercase();
-                            
-                            // Determine new-window flag based on browser
-                            let new_window_arg = if prog_id_lower.contains("firefox") {
-                                "-new-window"
-                            } else {
-                                "--new-window"
-                            };
-                            
-                            if Command::new(&browser_path)
-                                .args([new_window_arg, &url])
-                                .spawn()
-                                .is_ok()
-                            {
-                                opened = true;
-                            }
-                        }
-                    }
-                }
-                
-                // Fallback: try common browser paths
-                if !opened {
-                    let browser_paths = [
-                        (r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe", "--new-window"),
-                        (r"C:\Program Files\Microsoft\Edge\Application\msedge.exe", "--new-window"),
-                        (r"C:\Program Files\Google\Chrome\Application\chrome.exe", "--new-window"),
-                        (r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe", "--new-window"),
-                        (r"C:\Program Files\Mozilla Firefox\firefox.exe", "-new-window"),
-                        (r"C:\Program Files\BraveSoftware\Brave-Browser\Application\brave.exe", "--new-window"),
-                    ];
-                    
-                    for (path, arg) in browser_paths {
-                        if std::path::Path::new(path).exists() {
-                            if Command::new(path)
-                                .args([arg, &url])
-                                .spawn()
-                                .is_ok()
-                            {
-                                opened = true;
-                                break;
-                            }
-                        }
-                    }
-                }
-                
-                // Last resort: use start command (won't guarantee new window)
-                if !opened {
-                    let _ = Command::new("cmd")
-                        .args(["/c", "start", "", &url])
-                        .spawn()
-                        .map_err(|e| format!("Failed to open URL {}: {}", url, e))?;
-                }
-            }
+#[tauri::command]
+fn delete_shortcut(id: String, expected_revision: Option<u64>) -> Result<MutationResult, String> {
+    mutate(
+        ChangeKind::DeleteShortcut,
+        expected_revision,
+        |data, trash| {
+            let shortcut = crud::delete_shortcut(data, &id)?;
+            trash
+                .added
+                .push(TrashEntry::new(TrashedItem::Shortcut { shortcut }));
+            Ok(None)
+        },
+    )
+}
 
-            #[cfg(not(windows))]
-            {
-                open::that(&url).map_err(|e| format!("Failed to open URL {}: {}", url, e))?;
-            }
+/// ショートカットを `group_id` の `index` 番目へ移動する（同じグループ内の並べ替えも可）
+#[tauri::command]
+fn move_shortcut(
+    id: String,
+    group_id: String,
+    index: usize,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    mutate(ChangeKind::MoveShortcut, expected_revision, |data, _| {
+        crud::move_shortcut(data, &id, &group_id, index).map(|_| None)
+    })
+}
 
-            // If window config is set, wait and then set window position
-            #[cfg(windows)]
-            if let Some(config) = window_config {
-                tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
-
-                // Find the new browser window by checking foreground window
-                let mut target_hwnd: Option<windows::Win32::Foundation::HWND> = None;
-                
-                // First try: get the foreground window if it's a browser
-                unsafe {
-                    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
-                    let fg_hwnd = GetForegroundWindow();
-                    if !fg_hwnd.is_invalid() {
-                        // Check if foreground window belongs to a browser
-                        for browser in ["chrome.exe", "msedge.exe", "firefox.exe", "brave.exe", "opera.exe"] {
-                            let browser_windows = window_control::get_windows_by_process_name(browser);
-                            for (hwnd, _) in &browser_windows {
-                                if hwnd.0 == fg_hwnd.0 {
-                                    target_hwnd = Some(*hwnd);
-                                    break;
-                                }
-                            }
-                            if target_hwnd.is_some() {
-                                break;
-                            }
-                        }
-                    }
-                }
-                
-                // Second try: find new window by comparing before/after
-                if target_hwnd.is_none() {
-                    for browser in ["chrome.exe", "msedge.exe", "firefox.exe", "brave.exe", "opera.exe"] {
-                        let after_windows = window_control::get_windows_by_process_name(browser);
-                        for (hwnd, _) in after_windows {
-                            if !before_windows.contains(&(hwnd.0 as isize)) {
-                                target_hwnd = Some(hwnd);
-                                break;
-                            }
-                        }
-                        if target_hwnd.is_some() {
-                            break;
-                        }
-                    }
-                }
+#[tauri::command]
+fn reorder_shortcuts(
+    group_id: String,
+    ids: Vec<String>,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    mutate(
+        ChangeKind::ReorderShortcuts,
+        expected_revision,
+        |data, _| crud::reorder_shortcuts(data, &group_id, &ids).map(|_| None),
+    )
+}
 
-                if let Some(hwnd) = target_hwnd {
-                    if let Some((cur_x, cur_y, cur_w, cur_h)) = window_control::get_window_rect(hwnd) {
-                        let x = config.x.unwrap_or(cur_x);
-                        let y = config.y.unwrap_or(cur_y);
-                        let w = config.width.unwrap_or(cur_w);
-                        let h = config.height.unwrap_or(cur_h);
-                        let _ = window_control::set_window_position(hwnd, x, y, w, h);
-                    }
-                }
-            }
+#[tauri::command]
+fn create_group(
+    group: GroupInput,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    mutate(ChangeKind::CreateGroup, expected_revision, |data, _| {
+        Ok(Some(crud::create_group(data, group)))
+    })
+}
 
-            Ok(format!("Opened URL: {}", url))
-        }
+#[tauri::command]
+fn update_group(
+    id: String,
+    group: GroupInput,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    mutate(ChangeKind::UpdateGroup, expected_revision, |data, _| {
+        crud::update_group(data, &id, group).map(|_| None)
+    })
+}
 
-        Action::Delay { ms } => {
-            tokio::time::sleep(tokio::time::Duration::from_millis(ms)).await;
-            Ok(format!("Delayed for {}ms", ms))
-        }
-    }
+/// グループを削除する。所属していたショートカットはデフォルトグループへ移る
+#[tauri::command]
+fn delete_group(id: String, expected_revision: Option<u64>) -> Result<MutationResult, String> {
+    mutate(ChangeKind::DeleteGroup, expected_revision, |data, trash| {
+        let (group, shortcut_ids) = crud::delete_group(data, &id)?;
+        trash.added.push(TrashEntry::new(TrashedItem::Group {
+            group,
+            shortcut_ids,
+        }));
+        Ok(None)
+    })
 }
 
 #[tauri::command]
-async fn execute_shortcut(shortcut: Shortcut) -> Result<Vec<String>, String> {
-    let mut results = Vec::new();
+fn move_group(
+    id: String,
+    index: usize,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    mutate(ChangeKind::MoveGroup, expected_revision, |data, _| {
+        crud::move_group(data, &id, index).map(|_| None)
+    })
+}
 
-    for action in shortcut.actions {
-        match execute_action(action).await {
-            Ok(msg) => results.push(msg),
-            Err(e) => results.push(format!("Error: {}", e)),
-        }
-    }
+#[tauri::command]
+fn reorder_groups(
+    ids: Vec<String>,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    mutate(ChangeKind::ReorderGroups, expected_revision, |data, _| {
+        crud::reorder_groups(data, &ids).map(|_| None)
+    })
+}
 
-    Ok(results)
+#[tauri::command]
+fn undo_change() -> Result<MutationResult, String> {
+    let data = journal::undo()?;
+    Ok(MutationResult {
+        validation: validation::validate(&data),
+        data,
+        id: None,
+        undo: journal::status(),
+    })
 }
 
 #[tauri::command]
-fn get_shortcuts() -> Result<Vec<Shortcut>, String> {
-    let data = load_app_data();
-    Ok(data.shortcuts)
+fn redo_change() -> Result<MutationResult, String> {
+    let data = journal::redo()?;
+    Ok(MutationResult {
+        validation: validation::validate(&data),
+        data,
+        id: None,
+        undo: journal::status(),
+    })
 }
 
 #[tauri::command]
-fn save_shortcuts(shortcuts: Vec<Shortcut>) -> Result<(), String> {
-    let mut data = load_app_data();
-    data.shortcuts = shortcuts;
-    save_app_data(&data)
+fn get_undo_status() -> UndoStatus {
+    journal::status()
 }
 
+/// 最近削除したショートカットとグループ（新しい順）
 #[tauri::command]
-fn get_groups() -> Result<Vec<Group>, String> {
-    let data = load_app_data();
-    Ok(data.groups)
+fn list_trash() -> Result<Vec<TrashEntry>, String> {
+    trash::list()
 }
 
 #[tauri::command]
-fn save_groups(groups: Vec<Group>) -> Result<(), String> {
-    let mut data = load_app_data();
-    data.groups = groups;
-    save_app_data(&data)
+fn restore_from_trash(
+    entry_id: String,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    mutate(
+        ChangeKind::RestoreFromTrash,
+        expected_revision,
+        |data, trash| {
+            let entry = trash::get(&entry_id)?;
+            let id = match entry.item.clone() {
+                TrashedItem::Shortcut { shortcut } => crud::restore_shortcut(data, shortcut)?,
+                TrashedItem::Group {
+                    group,
+                    shortcut_ids,
+                } => crud::restore_group(data, group, &shortcut_ids)?,
+            };
+            trash.removed = Some(entry);
+            Ok(Some(id))
+        },
+    )
+}
+
+/// 選択したショートカットとグループをバンドルファイルに書き出す
+/// （`selection` を省略するとすべて）
+#[tauri::command]
+fn export_bundle(
+    path: String,
+    selection: Option<ExportSelection>,
+) -> Result<ExportSummary, String> {
+    export_bundle_to_file(&PathBuf::from(path), &selection.unwrap_or_default())
+}
+
+#[tauri::command]
+fn import_bundle(
+    path: String,
+    options: Option<ImportOptions>,
+    expected_revision: Option<u64>,
+) -> Result<ImportResult, String> {
+    let bundle = read_bundle(&PathBuf::from(path))?;
+    let (mutation, report) =
+        import_bundle_data(bundle, &options.unwrap_or_default(), expected_revision)?;
+    Ok(ImportResult { mutation, report })
+}
+
+/// 保存せずに、取り込んだ場合の作成・衝突の内容だけを返す
+#[tauri::command]
+fn preview_bundle_import(
+    path: String,
+    options: Option<ImportOptions>,
+) -> Result<ImportReport, String> {
+    let bundle = read_bundle(&PathBuf::from(path))?;
+    preview_import(bundle, &options.unwrap_or_default())
+}
+
+/// ショートカットを、チャットなどに貼り付けて共有できるテキストにする
+#[tauri::command]
+fn create_share_code(shortcut_id: String) -> Result<String, String> {
+    let data = load_app_data()?;
+    let shortcut = data
+        .shortcuts
+        .iter()
+        .find(|s| s.id == shortcut_id)
+        .ok_or_else(|| format!("Shortcut not found: {}", shortcut_id))?;
+    share_code::encode(shortcut)
+}
+
+/// 共有コードの中身を確認する（保存はしない）
+#[tauri::command]
+fn preview_share_code(code: String) -> Result<SharedShortcut, String> {
+    share_code::decode(&code)
+}
+
+#[tauri::command]
+fn import_share_code(
+    code: String,
+    group_id: Option<String>,
+    expected_revision: Option<u64>,
+) -> Result<MutationResult, String> {
+    import_share_code_data(&code, group_id, expected_revision)
+}
+
+/// ゴミ箱から完全に削除する。`entry_id` を省略するとゴミ箱を空にする
+#[tauri::command]
+fn purge_trash(entry_id: Option<String>) -> Result<usize, String> {
+    trash::purge(entry_id.as_deref())
 }
 
 #[tauri::command]
@@ -1084,12 +965,109 @@ fn get_process_list() -> Vec<ProcessInfo> {
 
 #[tauri::command]
 fn get_app_data() -> Result<AppData, String> {
-    Ok(load_app_data())
+    let data = load_app_data()?;
+    // 読み込み時は拒否せず、問題をログに残すだけにする
+    for issue in validation::validate(&data).issues {
+        eprintln!("App data {:?}: {}", issue.severity, issue.message);
+    }
+    Ok(data)
 }
 
 #[tauri::command]
-fn save_app_data_cmd(data: AppData) -> Result<(), String> {
-    save_app_data(&data)
+fn save_app_data_cmd(data: AppData) -> Result<SaveResult, String> {
+    // data.revision は読み込み時のリビジョン。壊れた data.json はここでは
+    // 上書きされず（読み込みで失敗する）、復旧コマンドで扱う
+    save_validated_app_data(Some(data.revision), |current| *current = data)
+}
+
+/// 保存せずに検証だけ行う。`data` を省略すると保存済みのデータを検証する
+#[tauri::command]
+fn validate_app_data(data: Option<AppData>) -> Result<ValidationReport, String> {
+    let data = match data {
+        Some(data) => data,
+        None => load_app_data()?,
+    };
+    Ok(validation::validate(&data))
+}
+
+/// 退避された壊れた data.json の一覧（新しい順）
+#[tauri::command]
+fn list_quarantined_data() -> Vec<QuarantinedFile> {
+    storage::list_quarantined()
+}
+
+/// 手で修復した退避ファイルを data.json に戻す
+#[tauri::command]
+fn restore_quarantined_data(file_name: String) -> Result<AppData, String> {
+    storage::restore_quarantined(&file_name)
+}
+
+/// 壊れた data.json を退避して初期データからやり直す
+#[tauri::command]
+fn reset_app_data() -> Result<AppData, String> {
+    storage::reset_app_data()
+}
+
+/// 自動バックアップの一覧（新しい順）
+#[tauri::command]
+fn list_data_backups() -> Vec<BackupInfo> {
+    storage::list_backups()
+}
+
+/// バックアップから data.json を復元する（復元前の内容もバックアップされる）
+#[tauri::command]
+fn restore_data_backup(file_name: String) -> Result<AppData, String> {
+    storage::restore_backup(&file_name)
+}
+
+#[tauri::command]
+fn list_profiles() -> Vec<ProfileInfo> {
+    profiles::list()
+}
+
+#[tauri::command]
+fn create_profile(name: String) -> Result<Vec<ProfileInfo>, String> {
+    profiles::create(&name)?;
+    Ok(profiles::list())
+}
+
+/// `source` のショートカットとグループをコピーして新しいプロファイルを作る
+#[tauri::command]
+fn duplicate_profile(source: String, name: String) -> Result<Vec<ProfileInfo>, String> {
+    profiles::duplicate(&source, &name)?;
+    Ok(profiles::list())
+}
+
+#[tauri::command]
+fn delete_profile(name: String) -> Result<Vec<ProfileInfo>, String> {
+    profiles::delete(&name)?;
+    Ok(profiles::list())
+}
+
+/// プロファイルを切り替えて、そのデータを返す。元に戻す履歴は前のプロファイルの
+/// ものなので破棄する
+#[tauri::command]
+fn switch_profile(name: String) -> Result<AppData, String> {
+    profiles::switch(&name)?;
+    journal::clear();
+    load_app_data()
+}
+
+/// データフォルダの場所と、それを決めた設定
+#[tauri::command]
+fn get_data_location() -> DataLocation {
+    data_dir::location().clone()
+}
+
+#[tauri::command]
+fn get_data_format() -> DataFormat {
+    storage::current_format()
+}
+
+/// データファイルを指定した書式で書き直す。書き直したファイルのパスを返す
+#[tauri::command]
+fn set_data_format(format: DataFormat) -> Result<String, String> {
+    storage::convert_data_file(format).map(|path| path.to_string_lossy().to_string())
 }
 
 // ========================================
@@ -1284,18 +1262,18 @@ fn get_window_position(process_name: String) -> Result<WindowConfig, String> {
 fn get_window_list() -> Vec<WindowInfo> {
     #[cfg(windows)]
     {
+        use std::collections::HashMap;
+        use sysinfo::{ProcessesToUpdate, System};
         use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT};
         use windows::Win32::UI::WindowsAndMessaging::{
-            EnumWindows, GetWindowRect, GetWindowTextLengthW, GetWindowTextW,
-            GetWindowThreadProcessId, IsWindowVisible, GetWindow, GW_OWNER,
+            EnumWindows, GetWindow, GetWindowRect, GetWindowTextLengthW, GetWindowTextW,
+            GetWindowThreadProcessId, IsWindowVisible, GW_OWNER,
         };
-        use sysinfo::{ProcessesToUpdate, System};
-        use std::collections::HashMap;
 
         // プロセス情報を取得
         let mut sys = System::new_all();
         sys.refresh_processes(ProcessesToUpdate::All, true);
-        
+
         let process_map: HashMap<u32, String> = sys
             .processes()
             .iter()
@@ -1407,7 +1385,7 @@ pub struct DesktopShortcutOptions {
     pub icon_path: Option<String>,
     pub icon_index: Option<i32>,
     pub custom_icon_data: Option<String>, // Base64 encoded image data
-    pub border_radius: u32, // 0-50 for percentage
+    pub border_radius: u32,               // 0-50 for percentage
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -1448,15 +1426,20 @@ fn create_windows_shortcut(
     use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
 
     // Get the current executable path
-    let exe_path = std::env::current_exe()
-        .map_err(|e| format!("Failed to get executable path: {}", e))?;
+    let exe_path =
+        std::env::current_exe().map_err(|e| format!("Failed to get executable path: {}", e))?;
     let exe_path_str = exe_path.to_string_lossy().to_string();
 
     // Create arguments for the shortcut execution
-    let args = format!(
-        "--execute-shortcut {}",
-        request.shortcut_id
-    );
+    let mut args = format!("--execute-shortcut {}", request.shortcut_id);
+    // 別のデータフォルダで起動している場合は、同じデータを使うよう引き継ぐ
+    for arg in data_dir::forwarded_args() {
+        args.push_str(&format!(" \"{}\"", arg));
+    }
+    let profile = profiles::active();
+    if profile != profiles::DEFAULT_PROFILE {
+        args.push_str(&format!(" --profile \"{}\"", profile));
+    }
 
     // Process icon if custom icon data is provided
     let icon_path = if let Some(icon_data) = &request.options.custom_icon_data {
@@ -1484,7 +1467,10 @@ fn create_windows_shortcut(
             .map_err(|e| format!("Failed to create ShellLink: {}", e))?;
 
         // Set target path (the app executable)
-        let wide_target: Vec<u16> = exe_path_str.encode_utf16().chain(std::iter::once(0)).collect();
+        let wide_target: Vec<u16> = exe_path_str
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
         shell_link
             .SetPath(PCWSTR(wide_target.as_ptr()))
             .map_err(|e| format!("Failed to set path: {}", e))?;
@@ -1517,7 +1503,10 @@ fn create_windows_shortcut(
 
         // Set description
         let description = format!("Advanced Shortcut - {}", request.options.name);
-        let wide_desc: Vec<u16> = description.encode_utf16().chain(std::iter::once(0)).collect();
+        let wide_desc: Vec<u16> = description
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
         shell_link
             .SetDescription(PCWSTR(wide_desc.as_ptr()))
             .map_err(|e| format!("Failed to set description: {}", e))?;
@@ -1547,22 +1536,23 @@ fn create_windows_shortcut(
 
 #[cfg(windows)]
 fn get_icons_dir() -> PathBuf {
-    dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("advanced-shortcut")
-        .join("icons")
+    get_data_dir().join("icons")
 }
 
 #[cfg(windows)]
-fn create_ico_from_base64(base64_data: &str, output_path: &PathBuf, border_radius: u32) -> Result<(), String> {
+fn create_ico_from_base64(
+    base64_data: &str,
+    output_path: &PathBuf,
+    border_radius: u32,
+) -> Result<(), String> {
     // Decode base64 data
     let image_data = base64::engine::general_purpose::STANDARD
         .decode(base64_data)
         .map_err(|e| format!("Failed to decode base64: {}", e))?;
 
     // Load image
-    let img = image::load_from_memory(&image_data)
-        .map_err(|e| format!("Failed to load image: {}", e))?;
+    let img =
+        image::load_from_memory(&image_data).map_err(|e| format!("Failed to load image: {}", e))?;
 
     // Resize to standard icon sizes
     let sizes = [256, 128, 64, 48, 32, 16];
@@ -1584,14 +1574,17 @@ fn create_ico_from_base64(base64_data: &str, output_path: &PathBuf, border_radiu
         let raw_data = rgba_img.into_raw();
 
         let ico_image = ico::IconImage::from_rgba_data(width, height, raw_data);
-        icon_dir.add_entry(ico::IconDirEntry::encode(&ico_image)
-            .map_err(|e| format!("Failed to encode icon: {}", e))?);
+        icon_dir.add_entry(
+            ico::IconDirEntry::encode(&ico_image)
+                .map_err(|e| format!("Failed to encode icon: {}", e))?,
+        );
     }
 
     // Save ICO file
-    let file = fs::File::create(output_path)
-        .map_err(|e| format!("Failed to create ico file: {}", e))?;
-    icon_dir.write(file)
+    let file =
+        fs::File::create(output_path).map_err(|e| format!("Failed to create ico file: {}", e))?;
+    icon_dir
+        .write(file)
         .map_err(|e| format!("Failed to write ico file: {}", e))?;
 
     Ok(())
@@ -1761,21 +1754,53 @@ fn resolve_shortcut_link(lnk_path: String) -> Result<ShortcutResolveResponse, St
 // Plugin Setup
 // ========================================
 
+/// コマンドライン引数を解釈する。`None` ならコマンドは無く GUI を起動する。
+/// エラーは表示するメッセージと終了コードの組
+fn command_from_args() -> Option<Result<cli::Command, (String, i32)>> {
+    // --profile はどの起動方法でも最初に適用する
+    if let Err(e) = profiles::init_from_args() {
+        return Some(Err((e, cli::EXIT_ERROR)));
+    }
+    cli::parse(std::env::args().skip(1))
+        .map_err(|e| {
+            (
+                format!("{}\nRun with --help for usage.", e),
+                cli::EXIT_USAGE,
+            )
+        })
+        .transpose()
+}
+
+fn run_command(command: Result<cli::Command, (String, i32)>) -> i32 {
+    match command {
+        Ok(command) => cli::run(command),
+        Err((message, code)) => {
+            eprintln!("Error: {}", message);
+            code
+        }
+    }
+}
+
+/// コンソール版の実行ファイル（`advanced-shortcut-cli`）の入口。GUI は起動せず、
+/// コマンドが無ければ使い方を表示する
+pub fn run_cli() -> i32 {
+    match command_from_args() {
+        Some(command) => run_command(command),
+        None => {
+            cli::run(cli::Command::Help);
+            cli::EXIT_USAGE
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // CLI引数をチェック
-    if let Some(cli_request) = parse_cli_shortcut_request() {
-        // CLI引数がある場合はフロントエンドを起動せずに直接ショートカットを実行
-        match execute_shortcut_from_cli(&cli_request) {
-            Ok(_) => {
-                // 正常終了
-                std::process::exit(0);
-            }
-            Err(e) => {
-                eprintln!("Error executing shortcut: {}", e);
-                std::process::exit(1);
-            }
-        }
+    // コマンドがあればフロントエンドを起動せずに実行し、その結果で終了する
+    if let Some(command) = command_from_args() {
+        // リリース版はウィンドウアプリなので、出力を呼び出し元のコンソールに出す
+        #[cfg(windows)]
+        cli::attach_parent_console();
+        std::process::exit(run_command(command));
     }
 
     // CLI引数がない場合は通常のGUIアプリとして起動
@@ -1785,13 +1810,55 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .manage(RunRegistry::default())
+        .setup(|app| {
+            // data.json の手動編集や同期ツールによる変更を UI に反映する
+            watcher::start(app.handle().clone());
+            // 2回目以降の起動からのショートカット実行をこのアプリで引き受ける
+            instance::start(app.handle().clone());
+            // advanced-shortcut:// のリンクをこのアプリで開けるようにする
+            #[cfg(windows)]
+            if let Err(e) = link::register_scheme() {
+                eprintln!("{}", e);
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             execute_action,
             execute_shortcut,
+            plan_shortcut,
+            cancel_shortcut_run,
+            get_active_runs,
+            get_execution_history,
+            prune_execution_history,
+            get_history_retention,
+            set_history_retention,
             get_shortcuts,
             save_shortcuts,
             get_groups,
             save_groups,
+            create_shortcut,
+            update_shortcut,
+            delete_shortcut,
+            move_shortcut,
+            reorder_shortcuts,
+            create_group,
+            update_group,
+            delete_group,
+            move_group,
+            reorder_groups,
+            undo_change,
+            redo_change,
+            get_undo_status,
+            list_trash,
+            restore_from_trash,
+            purge_trash,
+            export_bundle,
+            import_bundle,
+            preview_bundle_import,
+            create_share_code,
+            preview_share_code,
+            import_share_code,
             get_process_list,
             get_installed_apps,
             get_window_position,
@@ -1799,6 +1866,20 @@ pub fn run() {
             resolve_shortcut_link,
             get_app_data,
             save_app_data_cmd,
+            validate_app_data,
+            list_quarantined_data,
+            restore_quarantined_data,
+            reset_app_data,
+            list_data_backups,
+            restore_data_backup,
+            get_data_location,
+            list_profiles,
+            create_profile,
+            duplicate_profile,
+            delete_profile,
+            switch_profile,
+            get_data_format,
+            set_data_format,
             create_desktop_shortcut,
             get_desktop_path,
             exit_app,
@@ -0,0 +1,156 @@
+use serde_json::Value;
+
+// ========================================
+// Schema Versions
+// ========================================
+
+/// data.json のスキーマバージョン。`AppData` の形を変えたら上げて、
+/// [`MIGRATIONS`] に移行処理を追加する。
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// `MIGRATIONS[n]` upgrades a file from schema version `n` to `n + 1`.
+const MIGRATIONS: &[Migration] = &[v0_to_v1];
+
+/// The version a file was written with. Files from before versioning have no
+/// `schemaVersion` field and count as version 0.
+pub fn schema_version(value: &Value) -> Result<u32, String> {
+    match value.get("schemaVersion") {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| format!("Invalid schemaVersion: {}", version)),
+    }
+}
+
+/// 古いスキーマのデータを1段階ずつ現在のバージョンまで移行する。
+/// Returns the version the data had before migrating.
+pub fn migrate(value: &mut Value) -> Result<u32, String> {
+    if !value.is_object() {
+        return Err("Data is not a JSON object".to_string());
+    }
+
+    let from = schema_version(value)?;
+    if from > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "Data was written by a newer version of the app (schema {}, this version supports up to {})",
+            from, CURRENT_SCHEMA_VERSION
+        ));
+    }
+
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        migration(value)
+            .map_err(|e| format!("Failed to migrate data from schema {}: {}", version, e))?;
+        value["schemaVersion"] = Value::from(version as u32 + 1);
+    }
+    Ok(from)
+}
+
+// ========================================
+// Migrations
+// ========================================
+
+/// v0 → v1: introduces `schemaVersion`.
+///
+/// The per-action `onError`/`timeoutMs` and the per-shortcut `onError` added
+/// alongside it are optional, so unversioned files already read as v1 and
+/// only need the version stamp.
+fn v0_to_v1(_value: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, AppData, ErrorPolicy};
+
+    const V0: &str = include_str!("fixtures/v0.json");
+    const V1: &str = include_str!("fixtures/v1.json");
+
+    fn migrate_fixture(fixture: &str) -> (AppData, u32, Value) {
+        let mut value: Value = serde_json::from_str(fixture).unwrap();
+        let from = migrate(&mut value).unwrap();
+        let data = serde_json::from_value(value.clone()).unwrap();
+        (data, from, value)
+    }
+
+    #[test]
+    fn every_version_has_a_migration() {
+        assert_eq!(MIGRATIONS.len(), CURRENT_SCHEMA_VERSION as usize);
+    }
+
+    #[test]
+    fn migrates_unversioned_file() {
+        let (data, from, value) = migrate_fixture(V0);
+
+        assert_eq!(from, 0);
+        assert_eq!(value["schemaVersion"], CURRENT_SCHEMA_VERSION);
+        assert_eq!(data.shortcuts.len(), 2);
+        assert_eq!(data.groups.len(), 2);
+
+        let start = &data.shortcuts[0];
+        assert_eq!(start.on_error, ErrorPolicy::Continue);
+        assert_eq!(start.actions.len(), 4);
+        assert!(start
+            .actions
+            .iter()
+            .all(|step| step.on_error.is_none() && step.timeout_ms.is_none()));
+        assert!(matches!(
+            &start.actions[0].action,
+            Action::Launch { args: Some(args), window_config: Some(_), .. } if args == &["--new-window"]
+        ));
+        assert!(matches!(
+            &data.shortcuts[1].actions[0].action,
+            Action::Kill { process_name } if process_name == "Code.exe"
+        ));
+    }
+
+    #[test]
+    fn migrates_v1_file() {
+        let (data, from, _) = migrate_fixture(V1);
+
+        assert_eq!(from, 1);
+        let shortcut = &data.shortcuts[0];
+        assert_eq!(shortcut.on_error, ErrorPolicy::Abort);
+        assert_eq!(shortcut.actions[0].timeout_ms, Some(10000));
+        assert_eq!(
+            shortcut.actions[0].on_error,
+            Some(ErrorPolicy::Retry {
+                retries: 2,
+                delay_ms: 500,
+                backoff: 2.0,
+            })
+        );
+    }
+
+    #[test]
+    fn migration_is_idempotent() {
+        for fixture in [V0, V1] {
+            let mut value: Value = serde_json::from_str(fixture).unwrap();
+            migrate(&mut value).unwrap();
+            let once = value.clone();
+            assert_eq!(migrate(&mut value).unwrap(), CURRENT_SCHEMA_VERSION);
+            assert_eq!(value, once);
+        }
+    }
+
+    #[test]
+    fn rejects_newer_schema() {
+        let mut value: Value = serde_json::from_str(V1).unwrap();
+        value["schemaVersion"] = Value::from(CURRENT_SCHEMA_VERSION + 1);
+        let before = value.clone();
+
+        assert!(migrate(&mut value).is_err());
+        assert_eq!(value, before);
+    }
+
+    #[test]
+    fn rejects_invalid_schema_version() {
+        let mut value: Value = serde_json::from_str(V0).unwrap();
+        value["schemaVersion"] = Value::from("1");
+
+        assert!(migrate(&mut value).is_err());
+    }
+}
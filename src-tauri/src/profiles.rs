@@ -0,0 +1,237 @@
+use crate::storage::DATA_FILE_NAMES;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// ========================================
+// Profile Types
+// ========================================
+
+/// The profile whose data lives directly in the data folder, so installs
+/// from before profiles existed keep their data. It cannot be deleted.
+pub const DEFAULT_PROFILE: &str = "default";
+/// 既定以外のプロファイルは profiles/<名前>/ に保存する
+const PROFILES_DIR: &str = "profiles";
+/// 最後に切り替えたプロファイルを覚えておくファイル
+const PROFILES_FILE: &str = "profiles.json";
+const MAX_NAME_LEN: usize = 64;
+/// Windows ではフォルダ名として使えない名前
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// プロファイル（ショートカットとグループの組）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+    pub path: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfilesFile {
+    #[serde(default)]
+    active: Option<String>,
+}
+
+/// Active profile of this process. `None` until first resolved from
+/// `--profile` or profiles.json.
+static ACTIVE: Mutex<Option<String>> = Mutex::new(None);
+
+fn root_dir() -> PathBuf {
+    crate::data_dir::location().path.clone()
+}
+
+// ========================================
+// Active Profile
+// ========================================
+
+/// Apply `--profile <name>` for this process. The choice is not saved, so a
+/// CLI run does not change the profile the GUI opens with.
+pub fn init_from_args() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            let name = args
+                .next()
+                .ok_or_else(|| "Missing value for --profile".to_string())?;
+            require_profile(&name)?;
+            *ACTIVE.lock().unwrap() = Some(name);
+        }
+    }
+    Ok(())
+}
+
+pub fn active() -> String {
+    ACTIVE
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| {
+            load_profiles_file()
+                .active
+                .filter(|name| exists(name))
+                .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+        })
+        .clone()
+}
+
+/// Folder holding the data file, backups and trash of a profile.
+pub fn profile_dir(name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        root_dir()
+    } else {
+        root_dir().join(PROFILES_DIR).join(name)
+    }
+}
+
+/// 切り替えたプロファイルは次回の起動時にも使う
+pub fn switch(name: &str) -> Result<(), String> {
+    require_profile(name)?;
+    save_profiles_file(&ProfilesFile {
+        active: Some(name.to_string()),
+    })?;
+    *ACTIVE.lock().unwrap() = Some(name.to_string());
+    Ok(())
+}
+
+// ========================================
+// Manage Profiles
+// ========================================
+
+/// The default profile first, then the others by name.
+pub fn list() -> Vec<ProfileInfo> {
+    let active = active();
+    let mut names: Vec<String> = fs::read_dir(root_dir().join(PROFILES_DIR))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| {
+                    validate_name(name).is_ok() && !name.eq_ignore_ascii_case(DEFAULT_PROFILE)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort_by_key(|name| name.to_lowercase());
+    names.insert(0, DEFAULT_PROFILE.to_string());
+
+    names
+        .into_iter()
+        .map(|name| ProfileInfo {
+            active: name == active,
+            path: profile_dir(&name).to_string_lossy().to_string(),
+            name,
+        })
+        .collect()
+}
+
+/// Create an empty profile. It starts with the initial data on first load.
+pub fn create(name: &str) -> Result<(), String> {
+    let dir = new_profile_dir(name)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create profile: {}", e))
+}
+
+/// Copy the shortcuts and groups of `source` into a new profile. Backups,
+/// trash and history are not copied.
+pub fn duplicate(source: &str, name: &str) -> Result<(), String> {
+    require_profile(source)?;
+    let dir = new_profile_dir(name)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create profile: {}", e))?;
+
+    let source_dir = profile_dir(source);
+    let data_file = DATA_FILE_NAMES
+        .iter()
+        .find(|file| source_dir.join(file).is_file());
+    if let Some(file) = data_file {
+        // data ファイルは置き換えで保存されるので、コピー中に中途半端な内容を読むことはない
+        if let Err(e) = fs::copy(source_dir.join(file), dir.join(file)) {
+            fs::remove_dir_all(&dir).ok();
+            return Err(format!("Failed to copy profile data: {}", e));
+        }
+    }
+    Ok(())
+}
+
+/// 使用中のプロファイルと既定のプロファイルは削除できない
+pub fn delete(name: &str) -> Result<(), String> {
+    require_profile(name)?;
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile cannot be deleted".to_string());
+    }
+    if name == active() {
+        return Err(format!(
+            "Profile \"{}\" is in use; switch to another profile first",
+            name
+        ));
+    }
+    fs::remove_dir_all(profile_dir(name)).map_err(|e| format!("Failed to delete profile: {}", e))
+}
+
+// ========================================
+// Helpers
+// ========================================
+
+fn exists(name: &str) -> bool {
+    name == DEFAULT_PROFILE || (validate_name(name).is_ok() && profile_dir(name).is_dir())
+}
+
+fn require_profile(name: &str) -> Result<(), String> {
+    if exists(name) {
+        Ok(())
+    } else {
+        Err(format!("Profile not found: {}", name))
+    }
+}
+
+fn new_profile_dir(name: &str) -> Result<PathBuf, String> {
+    validate_name(name)?;
+    // Windows のファイル名は大文字小文字を区別しないので、名前の重複もそれに合わせる
+    if list().iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+        return Err(format!("Profile already exists: {}", name));
+    }
+    Ok(profile_dir(name))
+}
+
+/// Profile names become folder names, so only allow names that are valid
+/// (and not special) on every platform.
+fn validate_name(name: &str) -> Result<(), String> {
+    let invalid = name.trim() != name
+        || name.is_empty()
+        || name.len() > MAX_NAME_LEN
+        || name.starts_with('.')
+        || name.ends_with('.')
+        || RESERVED_NAMES
+            .iter()
+            .any(|reserved| name.eq_ignore_ascii_case(reserved))
+        || name
+            .chars()
+            .any(|c| c.is_control() || r#"<>:"/\|?*"#.contains(c));
+    if invalid {
+        return Err(format!("Invalid profile name: \"{}\"", name));
+    }
+    Ok(())
+}
+
+fn get_profiles_path() -> PathBuf {
+    root_dir().join(PROFILES_FILE)
+}
+
+fn load_profiles_file() -> ProfilesFile {
+    fs::read_to_string(get_profiles_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_profiles_file(file: &ProfilesFile) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    fs::create_dir_all(root_dir()).map_err(|e| format!("Failed to save profiles: {}", e))?;
+    crate::storage::write_atomic(&get_profiles_path(), json.as_bytes())
+        .map_err(|e| format!("Failed to save profiles: {}", e))
+}
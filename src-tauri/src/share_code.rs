@@ -0,0 +1,248 @@
+use crate::crud::ShortcutInput;
+use crate::{ActionStep, ErrorPolicy, Shortcut};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+// ========================================
+// Share Code Format
+// ========================================
+//
+// asc1.<base64url(deflate(json))>.<crc32 hex>
+//
+// The checksum covers everything before the last ".", so a code that was
+// cut off or edited by hand is rejected instead of importing garbage.
+
+/// Prefix of share codes. The number is bumped when the encoding changes.
+const CODE_PREFIX: &str = "asc";
+const CODE_VERSION: u32 = 1;
+/// 共有コードの中身のバージョン。形を変えたら上げる
+pub const SHARE_VERSION: u32 = 1;
+/// Limit for the decompressed payload, so a crafted code cannot exhaust memory.
+const MAX_PAYLOAD_BYTES: u64 = 1024 * 1024;
+
+/// 共有コードに含まれるショートカットの内容（id やグループは含めない）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedShortcut {
+    pub version: u32,
+    pub name: String,
+    pub icon: String,
+    pub actions: Vec<ActionStep>,
+    #[serde(default)]
+    pub on_error: ErrorPolicy,
+}
+
+impl SharedShortcut {
+    pub fn of(shortcut: &Shortcut) -> Self {
+        SharedShortcut {
+            version: SHARE_VERSION,
+            name: shortcut.name.clone(),
+            icon: shortcut.icon.clone(),
+            actions: shortcut.actions.clone(),
+            on_error: shortcut.on_error.clone(),
+        }
+    }
+
+    pub fn into_input(self, group_id: String) -> ShortcutInput {
+        ShortcutInput {
+            name: self.name,
+            icon: self.icon,
+            group_id,
+            actions: self.actions,
+            on_error: self.on_error,
+            // 共有されたショートカットをリンクから確認なしで実行させない
+            link_runnable: false,
+        }
+    }
+}
+
+// ========================================
+// Encode / Decode
+// ========================================
+
+pub fn encode(shortcut: &Shortcut) -> Result<String, String> {
+    let json = serde_json::to_vec(&SharedShortcut::of(shortcut))
+        .map_err(|e| format!("Failed to serialize shortcut: {}", e))?;
+
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(&json)
+        .and_then(|_| encoder.finish())
+        .map(|compressed| {
+            let body = format!(
+                "{}{}.{}",
+                CODE_PREFIX,
+                CODE_VERSION,
+                URL_SAFE_NO_PAD.encode(compressed)
+            );
+            format!("{}.{:08x}", body, crc32fast::hash(body.as_bytes()))
+        })
+        .map_err(|e| format!("Failed to compress shortcut: {}", e))
+}
+
+/// 共有コードを読み取る。改ざん・破損したコードや新しいバージョンのコードは拒否する
+pub fn decode(code: &str) -> Result<SharedShortcut, String> {
+    // チャットで折り返された改行や空白は無視する
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let (body, checksum) = code
+        .rsplit_once('.')
+        .ok_or_else(|| "Not a shortcut share code".to_string())?;
+    let (prefix, payload) = body
+        .split_once('.')
+        .ok_or_else(|| "Not a shortcut share code".to_string())?;
+    let version = prefix
+        .strip_prefix(CODE_PREFIX)
+        .and_then(|v| v.parse::<u32>().ok())
+        .ok_or_else(|| "Not a shortcut share code".to_string())?;
+    if version > CODE_VERSION {
+        return Err(format!(
+            "Share code was created by a newer version of the app (code version {}, this version supports up to {})",
+            version, CODE_VERSION
+        ));
+    }
+
+    let expected = u32::from_str_radix(checksum, 16)
+        .map_err(|_| "Share code is damaged (invalid checksum)".to_string())?;
+    if crc32fast::hash(body.as_bytes()) != expected {
+        return Err("Share code is damaged or was modified (checksum mismatch)".to_string());
+    }
+
+    let compressed = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|e| format!("Share code is damaged: {}", e))?;
+    let mut json = Vec::new();
+    DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_PAYLOAD_BYTES + 1)
+        .read_to_end(&mut json)
+        .map_err(|e| format!("Share code is damaged: {}", e))?;
+    if json.len() as u64 > MAX_PAYLOAD_BYTES {
+        return Err("Share code is too large".to_string());
+    }
+
+    parse_payload(&json)
+}
+
+fn parse_payload(json: &[u8]) -> Result<SharedShortcut, String> {
+    let value: serde_json::Value =
+        serde_json::from_slice(json).map_err(|e| format!("Failed to parse share code: {}", e))?;
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version > SHARE_VERSION as u64 {
+        return Err(format!(
+            "Share code was created by a newer version of the app (version {}, this version supports up to {})",
+            version, SHARE_VERSION
+        ));
+    }
+    // 未知のアクション種別などはここで弾かれる
+    serde_json::from_value(value)
+        .map_err(|e| format!("Share code is not compatible with this version: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{shortcut, step};
+    use crate::Action;
+
+    /// `encode` と同じ手順で任意の中身・バージョンのコードを作る
+    fn seal(code_version: u32, json: &[u8]) -> String {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(json).unwrap();
+        let body = format!(
+            "{}{}.{}",
+            CODE_PREFIX,
+            code_version,
+            URL_SAFE_NO_PAD.encode(encoder.finish().unwrap())
+        );
+        format!("{}.{:08x}", body, crc32fast::hash(body.as_bytes()))
+    }
+
+    fn sample_actions() -> Vec<ActionStep> {
+        vec![
+            step(Action::OpenUrl {
+                url: "https://example.com".to_string(),
+                window_config: None,
+            }),
+            step(Action::Delay { ms: 500 }),
+        ]
+    }
+
+    fn sample_code() -> String {
+        let source = Shortcut {
+            actions: sample_actions(),
+            link_runnable: true,
+            ..shortcut("s1", "Morning", "g1")
+        };
+        encode(&source).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let code = sample_code();
+        assert!(code.starts_with("asc1."));
+
+        // 折り返された改行は無視される
+        let wrapped = format!("{}\n  {}", &code[..20], &code[20..]);
+        let shared = decode(&wrapped).unwrap();
+        assert_eq!(shared.version, SHARE_VERSION);
+        assert_eq!(shared.name, "Morning");
+        assert_eq!(shared.icon, "zap");
+        assert_eq!(
+            serde_json::to_value(&shared.actions).unwrap(),
+            serde_json::to_value(sample_actions()).unwrap()
+        );
+        assert!(!shared.into_input("g2".to_string()).link_runnable);
+    }
+
+    #[test]
+    fn rejects_a_modified_payload() {
+        let code = sample_code();
+        let at = code.find('.').unwrap() + 3;
+        let replacement = if &code[at..at + 1] == "A" { "B" } else { "A" };
+        let modified = format!("{}{}{}", &code[..at], replacement, &code[at + 1..]);
+
+        let err = decode(&modified).unwrap_err();
+        assert!(err.contains("checksum mismatch"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_a_truncated_code() {
+        let code = sample_code();
+        for len in [code.len() - 2, code.len() - 12, code.len() / 2, 4] {
+            assert!(decode(&code[..len]).is_err(), "accepted {}", &code[..len]);
+        }
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let json = br#"{"version":1,"name":"A","icon":"zap","actions":[]}"#;
+        assert!(decode(&seal(CODE_VERSION, json)).is_ok());
+
+        let err = decode(&seal(CODE_VERSION + 1, json)).unwrap_err();
+        assert!(err.contains("newer version"), "{}", err);
+
+        let newer = format!(
+            r#"{{"version":{},"name":"A","icon":"zap","actions":[]}}"#,
+            SHARE_VERSION + 1
+        );
+        let err = decode(&seal(CODE_VERSION, newer.as_bytes())).unwrap_err();
+        assert!(err.contains("newer version"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_an_oversized_payload() {
+        let padding = " ".repeat(MAX_PAYLOAD_BYTES as usize);
+        let json = format!(
+            r#"{{"version":1,"name":"A","icon":"zap","actions":[]}}{}"#,
+            padding
+        );
+
+        let err = decode(&seal(CODE_VERSION, json.as_bytes())).unwrap_err();
+        assert_eq!(err, "Share code is too large");
+    }
+}
@@ -0,0 +1,608 @@
+use crate::data_format::DataFormat;
+use crate::migrations::{self, CURRENT_SCHEMA_VERSION};
+use crate::{get_data_dir, get_data_path, AppData, Group};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// ========================================
+// Storage Types
+// ========================================
+
+/// 壊れた data.json の退避コピー
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantinedFile {
+    pub file_name: String,
+    pub path: String,
+    pub size: u64,
+    pub modified_ms: u64,
+}
+
+/// 自動バックアップ（保存前の data.json）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub path: String,
+    pub size: u64,
+    pub created_ms: u64,
+    /// `None` if the backup cannot be parsed.
+    pub shortcut_count: Option<usize>,
+    pub group_count: Option<usize>,
+}
+
+/// data.json に書き出す形。バージョンはファイル形式の情報なので `AppData`
+/// 自体には持たせない。
+#[derive(Serialize)]
+struct VersionedAppData<'a> {
+    #[serde(rename = "schemaVersion")]
+    schema_version: u32,
+    #[serde(flatten)]
+    data: &'a AppData,
+}
+
+/// Names the data file may have, in the order they are looked for. The
+/// extension selects the format; see [`DataFormat::from_path`].
+pub const DATA_FILE_NAMES: [&str; 4] = ["data.json", "data.yaml", "data.yml", "data.toml"];
+
+const QUARANTINE_PREFIX: &str = "data.corrupt-";
+const BACKUP_PREFIX: &str = "data-";
+/// Backups taken before a schema migration. Never rotated away.
+const PRE_MIGRATION_PREFIX: &str = "pre-migration-";
+/// How many automatic backups to keep. Older ones are deleted on save.
+const MAX_BACKUPS: usize = 20;
+
+/// Prefix of the error returned when a save is based on an outdated revision.
+/// The frontend matches on it to reload instead of showing a plain error.
+pub const CONFLICT_ERROR_PREFIX: &str = "revision_conflict";
+
+/// The group every install starts with. It cannot be deleted and receives the
+/// shortcuts of deleted groups.
+pub const DEFAULT_GROUP_ID: &str = "default";
+
+// ========================================
+// Locking
+// ========================================
+
+/// ロックファイルに対する排他ロック。GUI の複数ウィンドウや CLI 実行の間で
+/// ファイルの読み書きを直列化する。ドロップ時に解放される。
+pub(crate) struct FileLock(fs::File);
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        self.0.unlock().ok();
+    }
+}
+
+/// Block until this process holds the lock on `path`. The lock is advisory:
+/// it only coordinates processes that take it.
+pub(crate) fn lock_file(path: &Path, what: &str) -> Result<FileLock, String> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {} lock: {}", what, e))?;
+    file.lock()
+        .map_err(|e| format!("Failed to lock {}: {}", what, e))?;
+    Ok(FileLock(file))
+}
+
+/// data.json.lock. Every path in this module that reads or writes the data
+/// file takes it.
+fn lock_data() -> Result<FileLock, String> {
+    lock_file(&get_data_dir().join("data.json.lock"), "data")
+}
+
+// ========================================
+// Load / Save
+// ========================================
+
+/// data.json を読み込む。ファイルが無ければ初期データを返す。
+///
+/// 解析できない場合は初期データで置き換えず、タイムスタンプ付きのコピーを
+/// 退避したうえでエラーを返す。元のファイルには手を付けないので、以降の
+/// `save_*` も読み込みで失敗し、ユーザーのデータを上書きすることはない。
+pub fn load_app_data() -> Result<AppData, String> {
+    let _lock = lock_data()?;
+    load_unlocked()
+}
+
+fn load_unlocked() -> Result<AppData, String> {
+    let path = get_data_path();
+    let format = data_format(&path);
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(default_app_data()),
+        Err(e) => return Err(format!("Failed to read data: {}", e)),
+    };
+
+    let (data, from) = match parse_app_data(&content, format) {
+        Ok(parsed) => parsed,
+        // 新しいバージョンのアプリで書かれたファイルは壊れているわけではない
+        Err(e) if written_by_newer_version(&content, format) => return Err(e),
+        Err(e) => {
+            let saved_to = match quarantine(&content, format) {
+                Ok(copy) => format!("a copy was saved to {}", copy.display()),
+                Err(err) => err,
+            };
+            return Err(format!(
+                "{} is corrupt ({}); {}",
+                file_name(&path),
+                e,
+                saved_to
+            ));
+        }
+    };
+
+    if from < CURRENT_SCHEMA_VERSION {
+        backup_before_migration(&content, from, format)?;
+        write_unlocked(&data)?;
+    }
+    Ok(data)
+}
+
+/// Parse the contents of a data file written with any supported schema
+/// version. Returns the data and the version it was written with.
+pub fn parse_app_data(content: &str, format: DataFormat) -> Result<(AppData, u32), String> {
+    let mut value = format.parse(content)?;
+    let from = migrations::migrate(&mut value)?;
+    let data = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    Ok((data, from))
+}
+
+/// データファイルの内容を書き出す（現在のスキーマバージョン付き）
+pub fn serialize_app_data(data: &AppData, format: DataFormat) -> Result<String, String> {
+    format
+        .serialize(&VersionedAppData {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            data,
+        })
+        .map_err(|e| format!("Failed to serialize data: {}", e))
+}
+
+fn written_by_newer_version(content: &str, format: DataFormat) -> bool {
+    format
+        .parse(content)
+        .ok()
+        .and_then(|value| migrations::schema_version(&value).ok())
+        .is_some_and(|version| version > CURRENT_SCHEMA_VERSION)
+}
+
+/// Format of a data, backup or quarantine file, from its extension.
+fn data_format(path: &Path) -> DataFormat {
+    DataFormat::from_path(path).unwrap_or_default()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// `true` for the names in [`DATA_FILE_NAMES`].
+pub fn is_data_file_name(name: &str) -> bool {
+    DATA_FILE_NAMES.contains(&name)
+}
+
+/// 現在のデータファイルの書式
+pub fn current_format() -> DataFormat {
+    data_format(&get_data_path())
+}
+
+/// Rewrite the data file in `format` (data.json → data.yaml など). The old
+/// file is backed up and removed, so only one data file is left.
+pub fn convert_data_file(format: DataFormat) -> Result<PathBuf, String> {
+    let _lock = lock_data()?;
+    let data = load_unlocked()?;
+    let old_path = get_data_path();
+    let new_path = get_data_dir().join(format!("data.{}", format.extension()));
+    if new_path == old_path {
+        return Ok(new_path);
+    }
+
+    backup_current()?;
+    write_data_file(&new_path, &data)?;
+    match fs::remove_file(&old_path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => {
+            return Err(format!(
+                "Wrote {} but failed to remove {}: {}",
+                file_name(&new_path),
+                file_name(&old_path),
+                e
+            ))
+        }
+    }
+    Ok(new_path)
+}
+
+/// 読み込み → 変更 → 保存をロックを保持したまま行う。
+///
+/// `expected_revision` が保存済みのリビジョンと異なる場合（別のウィンドウや
+/// CLI が先に保存した場合）は何も書かずに競合エラーを返す。`update` がエラーを
+/// 返した場合も保存しない。成功すると新しいリビジョンのデータを返す。
+pub fn update_app_data<T>(
+    expected_revision: Option<u64>,
+    update: impl FnOnce(&mut AppData) -> Result<T, String>,
+) -> Result<(AppData, T), String> {
+    let _lock = lock_data()?;
+    let mut data = load_unlocked()?;
+    check_revision(expected_revision, data.revision)?;
+
+    let current = data.revision;
+    let output = update(&mut data)?;
+    data.revision = current + 1;
+    write_unlocked(&data)?;
+    Ok((data, output))
+}
+
+fn check_revision(expected: Option<u64>, current: u64) -> Result<(), String> {
+    match expected {
+        Some(expected) if expected != current => Err(format!(
+            "{}: data was changed by another window or process (expected revision {}, found {})",
+            CONFLICT_ERROR_PREFIX, expected, current
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Replace data.json regardless of its current content (used for recovery,
+/// where the current file may not even parse). Still bumps the revision so
+/// other windows notice the change.
+fn replace_unlocked(data: &mut AppData) -> Result<(), String> {
+    let path = get_data_path();
+    let current = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| data_format(&path).parse(&content).ok())
+        .and_then(|value| value.get("revision").and_then(Value::as_u64))
+        .unwrap_or(0);
+    data.revision = data.revision.max(current) + 1;
+    write_unlocked(data)
+}
+
+/// data.json を書き込む。書き込み前の内容はバックアップに残す。
+fn write_unlocked(data: &AppData) -> Result<(), String> {
+    // バックアップの失敗で保存自体は止めない
+    if let Err(e) = backup_current() {
+        eprintln!("{}", e);
+    }
+    write_data_file(&get_data_path(), data)
+}
+
+fn write_data_file(path: &Path, data: &AppData) -> Result<(), String> {
+    let content = serialize_app_data(data, data_format(path))?;
+    // ファイル監視が自分の書き込みを外部変更と誤認しないよう、書く前に記録する
+    *LAST_WRITTEN.lock().unwrap() = Some(fingerprint(content.as_bytes()));
+    write_atomic(path, content.as_bytes()).map_err(|e| format!("Failed to write data: {}", e))
+}
+
+/// Fingerprint of the last content this process wrote to data.json.
+static LAST_WRITTEN: Mutex<Option<u64>> = Mutex::new(None);
+
+pub fn fingerprint(content: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// `true` if data.json content with this fingerprint was written by this
+/// process rather than by an external edit.
+pub fn is_own_write(fingerprint: u64) -> bool {
+    *LAST_WRITTEN.lock().unwrap() == Some(fingerprint)
+}
+
+/// Write `contents` to a temporary file next to `path`, flush it to disk and
+/// rename it over `path`, so a crash leaves either the old or the new file.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = (|| {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp_path, path)?;
+
+        // Make the rename itself durable. Directories cannot be opened this
+        // way on Windows, where the rename is already flushed by the OS.
+        #[cfg(unix)]
+        if let Some(dir) = path.parent() {
+            fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    })();
+
+    if result.is_err() {
+        fs::remove_file(&tmp_path).ok();
+    }
+    result
+}
+
+pub fn default_app_data() -> AppData {
+    AppData {
+        revision: 0,
+        shortcuts: vec![],
+        groups: vec![Group {
+            id: DEFAULT_GROUP_ID.to_string(),
+            name: "デフォルト".to_string(),
+            color: "#22d3ee".to_string(),
+            icon: "folder".to_string(),
+            order: 0,
+            is_expanded: true,
+        }],
+    }
+}
+
+// ========================================
+// Quarantine
+// ========================================
+
+/// Save a timestamped copy of unreadable content next to data.json. Repeated
+/// loads of the same broken file reuse the newest copy instead of piling up.
+fn quarantine(content: &str, format: DataFormat) -> Result<PathBuf, String> {
+    if let Some(latest) = list_quarantined().first() {
+        if fs::read_to_string(&latest.path).is_ok_and(|existing| existing == content) {
+            return Ok(PathBuf::from(&latest.path));
+        }
+    }
+
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let path = get_data_dir().join(format!(
+        "{}{}.{}",
+        QUARANTINE_PREFIX,
+        stamp,
+        format.extension()
+    ));
+    fs::write(&path, content).map_err(|e| format!("Failed to quarantine data: {}", e))?;
+    Ok(path)
+}
+
+/// Quarantined copies, newest first.
+pub fn list_quarantined() -> Vec<QuarantinedFile> {
+    let Ok(entries) = fs::read_dir(get_data_dir()) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<QuarantinedFile> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !file_name.starts_with(QUARANTINE_PREFIX) {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            Some(QuarantinedFile {
+                path: entry.path().to_string_lossy().to_string(),
+                file_name,
+                size: metadata.len(),
+                modified_ms: modified_ms(&metadata),
+            })
+        })
+        .collect();
+
+    // ファイル名のタイムスタンプ順 = 作成順
+    files.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+    files
+}
+
+/// Replace data.json with a quarantined copy, typically after the user has
+/// repaired it by hand. Fails without touching data.json if the copy still
+/// does not parse.
+pub fn restore_quarantined(file_name: &str) -> Result<AppData, String> {
+    let path = quarantined_path(file_name)?;
+    let _lock = lock_data()?;
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+    let (mut data, _) = parse_app_data(&content, data_format(&path))
+        .map_err(|e| format!("{} is still not valid: {}", file_name, e))?;
+
+    replace_unlocked(&mut data)?;
+    Ok(data)
+}
+
+/// Give up on the current data.json and start over with the initial data.
+/// The broken content is quarantined first so it can still be recovered.
+pub fn reset_app_data() -> Result<AppData, String> {
+    let _lock = lock_data()?;
+    let path = get_data_path();
+    let format = data_format(&path);
+    if let Ok(content) = fs::read_to_string(&path) {
+        if parse_app_data(&content, format).is_ok() || written_by_newer_version(&content, format) {
+            return Err(format!(
+                "{} is not corrupt; refusing to reset it",
+                file_name(&path)
+            ));
+        }
+        quarantine(&content, format)?;
+    }
+
+    let mut data = default_app_data();
+    replace_unlocked(&mut data)?;
+    Ok(data)
+}
+
+/// 履歴・ゴミ箱など、data.json 以外の JSON ファイルを読み込む。無ければ既定値。
+///
+/// A file that does not parse is not read as empty, or the next save would
+/// silently wipe it: it is renamed to `<name>.corrupt-<timestamp>.json` and
+/// reading starts over from the default. Callers hold the file's lock.
+pub(crate) fn load_json_file<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => return Err(format!("Failed to read {}: {}", file_name(path), e)),
+    };
+    let error = match serde_json::from_str(&content) {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let aside = path.with_file_name(format!("{}.corrupt-{}.json", stem, stamp));
+    fs::rename(path, &aside).map_err(|e| {
+        format!(
+            "{} is corrupt ({}) and could not be moved aside: {}",
+            file_name(path),
+            error,
+            e
+        )
+    })?;
+    eprintln!(
+        "{} is corrupt ({}); it was moved to {} and a new one is started",
+        file_name(path),
+        error,
+        aside.display()
+    );
+    Ok(T::default())
+}
+
+/// Only accept names produced by [`quarantine`], so the frontend cannot point
+/// us at arbitrary files.
+fn quarantined_path(file_name: &str) -> Result<PathBuf, String> {
+    if !file_name.starts_with(QUARANTINE_PREFIX) || file_name.contains(['/', '\\']) {
+        return Err(format!("Not a quarantined data file: {}", file_name));
+    }
+    Ok(get_data_dir().join(file_name))
+}
+
+// ========================================
+// Backups
+// ========================================
+
+fn get_backup_dir() -> PathBuf {
+    get_data_dir().join("backups")
+}
+
+/// Copy the current data.json into the backup folder and drop the oldest
+/// backups beyond [`MAX_BACKUPS`].
+fn backup_current() -> Result<(), String> {
+    let data_path = get_data_path();
+    let current = match fs::read(&data_path) {
+        Ok(current) => current,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("Failed to read data for backup: {}", e)),
+    };
+
+    let backup_dir = get_backup_dir();
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("Failed to create backup folder: {}", e))?;
+
+    // 同じ秒に複数回保存されても上書きしないようミリ秒まで含める
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S-%3f");
+    let path = backup_dir.join(format!(
+        "{}{}.{}",
+        BACKUP_PREFIX,
+        stamp,
+        data_format(&data_path).extension()
+    ));
+    write_atomic(&path, &current).map_err(|e| format!("Failed to write backup: {}", e))?;
+
+    let rotated = list_backups()
+        .into_iter()
+        .filter(|backup| backup.file_name.starts_with(BACKUP_PREFIX));
+    for old in rotated.skip(MAX_BACKUPS) {
+        fs::remove_file(&old.path).ok();
+    }
+    Ok(())
+}
+
+/// Keep the file exactly as it was before migrating it from schema `from`.
+fn backup_before_migration(content: &str, from: u32, format: DataFormat) -> Result<(), String> {
+    let backup_dir = get_backup_dir();
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("Failed to create backup folder: {}", e))?;
+
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let path = backup_dir.join(format!(
+        "{}v{}-{}.{}",
+        PRE_MIGRATION_PREFIX,
+        from,
+        stamp,
+        format.extension()
+    ));
+    write_atomic(&path, content.as_bytes())
+        .map_err(|e| format!("Failed to write pre-migration backup: {}", e))
+}
+
+fn is_backup_name(file_name: &str) -> bool {
+    (file_name.starts_with(BACKUP_PREFIX) || file_name.starts_with(PRE_MIGRATION_PREFIX))
+        && DataFormat::from_path(Path::new(file_name)).is_some()
+        && !file_name.contains(['/', '\\'])
+}
+
+/// Automatic and pre-migration backups, newest first.
+pub fn list_backups() -> Vec<BackupInfo> {
+    let Ok(entries) = fs::read_dir(get_backup_dir()) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<BackupInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !is_backup_name(&file_name) {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            let data = fs::read_to_string(entry.path())
+                .ok()
+                .and_then(|content| parse_app_data(&content, data_format(&entry.path())).ok())
+                .map(|(data, _)| data);
+            Some(BackupInfo {
+                path: entry.path().to_string_lossy().to_string(),
+                file_name,
+                size: metadata.len(),
+                created_ms: modified_ms(&metadata),
+                shortcut_count: data.as_ref().map(|d| d.shortcuts.len()),
+                group_count: data.as_ref().map(|d| d.groups.len()),
+            })
+        })
+        .collect();
+
+    backups.sort_by(|a, b| {
+        b.created_ms
+            .cmp(&a.created_ms)
+            .then_with(|| b.file_name.cmp(&a.file_name))
+    });
+    backups
+}
+
+/// Replace data.json with a backup. The data being replaced is itself backed
+/// up first, so a restore can be undone.
+pub fn restore_backup(file_name: &str) -> Result<AppData, String> {
+    if !is_backup_name(file_name) {
+        return Err(format!("Not a backup file: {}", file_name));
+    }
+
+    let path = get_backup_dir().join(file_name);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read backup {}: {}", file_name, e))?;
+    let (mut data, _) = parse_app_data(&content, data_format(&path))
+        .map_err(|e| format!("Backup {} is not valid: {}", file_name, e))?;
+
+    let _lock = lock_data()?;
+    replace_unlocked(&mut data)?;
+    Ok(data)
+}
+
+fn modified_ms(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,166 @@
+use crate::storage::{self, FileLock};
+use crate::{Group, Shortcut};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// ========================================
+// Trash Types
+// ========================================
+
+/// 削除されたショートカットまたはグループ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum TrashedItem {
+    Shortcut {
+        shortcut: Shortcut,
+    },
+    /// Deleting a group moves its shortcuts to the default group; their ids
+    /// are kept so restoring the group can move them back.
+    Group {
+        group: Group,
+        shortcut_ids: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub id: String,
+    pub deleted_at_ms: u64,
+    #[serde(flatten)]
+    pub item: TrashedItem,
+}
+
+impl TrashEntry {
+    pub fn new(item: TrashedItem) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            deleted_at_ms: crate::executor::now_ms(),
+            item,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrashFile {
+    /// Oldest first.
+    #[serde(default)]
+    entries: Vec<TrashEntry>,
+}
+
+/// How a data change affected the trash, so undo/redo can keep it in sync.
+#[derive(Debug, Clone, Default)]
+pub struct TrashDelta {
+    /// Entries created by deletes (one per shortcut when a bundle import
+    /// replaces a group).
+    pub added: Vec<TrashEntry>,
+    /// Entry consumed by a restore.
+    pub removed: Option<TrashEntry>,
+}
+
+impl TrashDelta {
+    pub fn apply(&self) -> Result<(), String> {
+        replace(self.removed.as_slice(), &self.added)
+    }
+
+    /// Undo [`apply`](Self::apply).
+    pub fn revert(&self) -> Result<(), String> {
+        replace(&self.added, self.removed.as_slice())
+    }
+}
+
+// ========================================
+// Trash Store
+// ========================================
+
+/// Keep at most this many entries (newest first).
+const MAX_TRASH_ENTRIES: usize = 100;
+/// Entries older than this are deleted for good.
+const MAX_TRASH_AGE_MS: u64 = 30 * 24 * 60 * 60 * 1000;
+
+fn get_trash_path() -> PathBuf {
+    crate::get_data_dir().join("trash.json")
+}
+
+/// trash.json.lock. The app and the command line (`import --groups replace`)
+/// may both add entries, so every access takes it, not only writes.
+fn lock_trash() -> Result<FileLock, String> {
+    storage::lock_file(&crate::get_data_dir().join("trash.json.lock"), "trash")
+}
+
+fn load_trash_file() -> Result<TrashFile, String> {
+    storage::load_json_file(&get_trash_path())
+}
+
+fn save_trash_file(file: &TrashFile) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize trash: {}", e))?;
+    storage::write_atomic(&get_trash_path(), json.as_bytes())
+        .map_err(|e| format!("Failed to write trash: {}", e))
+}
+
+fn apply_retention(file: &mut TrashFile, now_ms: u64) {
+    let cutoff = now_ms.saturating_sub(MAX_TRASH_AGE_MS);
+    file.entries.retain(|entry| entry.deleted_at_ms >= cutoff);
+    if file.entries.len() > MAX_TRASH_ENTRIES {
+        let excess = file.entries.len() - MAX_TRASH_ENTRIES;
+        file.entries.drain(..excess);
+    }
+}
+
+/// Remove `remove` (by id) and add `add` in one write.
+fn replace(remove: &[TrashEntry], add: &[TrashEntry]) -> Result<(), String> {
+    let _lock = lock_trash()?;
+    let mut file = load_trash_file()?;
+
+    file.entries.retain(|entry| {
+        !remove
+            .iter()
+            .chain(add)
+            .any(|changed| changed.id == entry.id)
+    });
+    file.entries.extend(add.iter().cloned());
+    file.entries.sort_by_key(|entry| entry.deleted_at_ms);
+    apply_retention(&mut file, crate::executor::now_ms());
+
+    save_trash_file(&file)
+}
+
+/// ゴミ箱の中身（新しい順）
+pub fn list() -> Result<Vec<TrashEntry>, String> {
+    let _lock = lock_trash()?;
+    let mut entries = load_trash_file()?.entries;
+    entries.reverse();
+    Ok(entries)
+}
+
+pub fn get(id: &str) -> Result<TrashEntry, String> {
+    let _lock = lock_trash()?;
+    load_trash_file()?
+        .entries
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("Trash entry not found: {}", id))
+}
+
+/// Delete one entry for good, or empty the trash when `id` is `None`.
+/// Returns how many entries were removed.
+pub fn purge(id: Option<&str>) -> Result<usize, String> {
+    let _lock = lock_trash()?;
+    let mut file = load_trash_file()?;
+    let before = file.entries.len();
+
+    file.entries
+        .retain(|entry| id.is_some_and(|id| entry.id != id));
+
+    let removed = before - file.entries.len();
+    if removed > 0 {
+        save_trash_file(&file)?;
+    }
+    Ok(removed)
+}
@@ -0,0 +1,389 @@
+use crate::executor;
+use crate::{Action, AppData};
+use serde::Serialize;
+use std::collections::HashSet;
+
+// ========================================
+// Validation Types
+// ========================================
+
+/// `Error` は保存を拒否し、`Warning` は保存した上で呼び出し元に返す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCode {
+    DuplicateShortcutId,
+    DuplicateGroupId,
+    /// A shortcut points at a group that does not exist.
+    DanglingGroup,
+    /// A path, process name or URL is empty.
+    EmptyTarget,
+    InvalidUrl,
+    /// The executable of a `launch` action cannot be found on this machine.
+    MissingLaunchTarget,
+    DelayTooLong,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub code: IssueCode,
+    pub message: String,
+    pub shortcut_id: Option<String>,
+    pub group_id: Option<String>,
+    pub action_index: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn has_errors(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.severity == Severity::Error)
+    }
+
+    /// All error messages joined into one line, for `Err(String)` results.
+    pub fn error_summary(&self) -> String {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .map(|issue| issue.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    fn push(
+        &mut self,
+        severity: Severity,
+        code: IssueCode,
+        message: String,
+    ) -> &mut ValidationIssue {
+        self.issues.push(ValidationIssue {
+            severity,
+            code,
+            message,
+            shortcut_id: None,
+            group_id: None,
+            action_index: None,
+        });
+        self.issues.last_mut().unwrap()
+    }
+}
+
+// ========================================
+// Validation
+// ========================================
+
+/// `delay` actions longer than this are almost certainly a typo (seconds
+/// entered as milliseconds ×1000 and so on).
+const MAX_DELAY_MS: u64 = 60 * 60 * 1000;
+
+/// AppData 全体を検査する。ファイルシステムは `launch` の実行ファイル確認にだけ使う。
+pub fn validate(data: &AppData) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let mut group_ids = HashSet::new();
+    for group in &data.groups {
+        if !group_ids.insert(group.id.as_str()) {
+            report
+                .push(
+                    Severity::Error,
+                    IssueCode::DuplicateGroupId,
+                    format!("Duplicate group id: {}", group.id),
+                )
+                .group_id = Some(group.id.clone());
+        }
+    }
+
+    let mut shortcut_ids = HashSet::new();
+    for shortcut in &data.shortcuts {
+        if !shortcut_ids.insert(shortcut.id.as_str()) {
+            report
+                .push(
+                    Severity::Error,
+                    IssueCode::DuplicateShortcutId,
+                    format!("Duplicate shortcut id: {}", shortcut.id),
+                )
+                .shortcut_id = Some(shortcut.id.clone());
+        }
+
+        if !group_ids.contains(shortcut.group_id.as_str()) {
+            let issue = report.push(
+                Severity::Error,
+                IssueCode::DanglingGroup,
+                format!(
+                    "Shortcut \"{}\" refers to missing group: {}",
+                    shortcut.name, shortcut.group_id
+                ),
+            );
+            issue.shortcut_id = Some(shortcut.id.clone());
+            issue.group_id = Some(shortcut.group_id.clone());
+        }
+
+        for (index, step) in shortcut.actions.iter().enumerate() {
+            let len = report.issues.len();
+            validate_action(&step.action, &shortcut.name, index, &mut report);
+            for issue in &mut report.issues[len..] {
+                issue.shortcut_id = Some(shortcut.id.clone());
+                issue.action_index = Some(index);
+            }
+        }
+    }
+
+    report
+}
+
+fn validate_action(
+    action: &Action,
+    shortcut_name: &str,
+    index: usize,
+    report: &mut ValidationReport,
+) {
+    let location = format!("\"{}\" action {}", shortcut_name, index + 1);
+
+    let target = match action {
+        Action::Launch { path, .. } | Action::OpenFolder { path, .. } => path,
+        Action::Kill { process_name } => process_name,
+        Action::OpenUrl { url, .. } => url,
+        Action::Delay { ms } => {
+            if *ms > MAX_DELAY_MS {
+                report.push(
+                    Severity::Error,
+                    IssueCode::DelayTooLong,
+                    format!(
+                        "{}: delay of {}ms exceeds the limit of {}ms",
+                        location, ms, MAX_DELAY_MS
+                    ),
+                );
+            }
+            return;
+        }
+    };
+
+    if target.trim().is_empty() {
+        report.push(
+            Severity::Error,
+            IssueCode::EmptyTarget,
+            format!("{}: {} target is empty", location, action.type_name()),
+        );
+        return;
+    }
+
+    match action {
+        Action::OpenUrl { url, .. }
+            if !executor::has_url_scheme(url) || url.chars().any(char::is_whitespace) =>
+        {
+            report.push(
+                Severity::Error,
+                IssueCode::InvalidUrl,
+                format!("{}: malformed URL: {}", location, url),
+            );
+        }
+        // 別の PC で作られたデータもあり得るので警告に留める。
+        // 実行時と同じく、変数は展開せずにそのままのパスを探す
+        Action::Launch { path, .. } if executor::find_executable(path).is_none() => {
+            let mut message = format!("{}: executable not found: {}", location, path);
+            if !executor::variable_names(path).is_empty() {
+                message.push_str(" (variables are not expanded)");
+            }
+            report.push(Severity::Warning, IssueCode::MissingLaunchTarget, message);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{app_data, group, shortcut, step};
+    use crate::Shortcut;
+
+    fn with_actions(id: &str, actions: Vec<Action>) -> Shortcut {
+        Shortcut {
+            actions: actions.into_iter().map(step).collect(),
+            ..shortcut(id, id, "default")
+        }
+    }
+
+    fn codes(data: &AppData) -> Vec<(Severity, IssueCode)> {
+        validate(data)
+            .issues
+            .iter()
+            .map(|issue| (issue.severity, issue.code))
+            .collect()
+    }
+
+    fn this_executable() -> String {
+        std::env::current_exe()
+            .unwrap()
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn valid_data_has_no_issues() {
+        let data = app_data(
+            vec![group("default", "Default", 0)],
+            vec![with_actions(
+                "s1",
+                vec![
+                    Action::Launch {
+                        path: this_executable(),
+                        args: None,
+                        window_config: None,
+                    },
+                    Action::OpenUrl {
+                        url: "https://example.com/?q=1".to_string(),
+                        window_config: None,
+                    },
+                    Action::Delay { ms: MAX_DELAY_MS },
+                ],
+            )],
+        );
+        let report = validate(&data);
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn duplicate_ids() {
+        let data = app_data(
+            vec![group("default", "Default", 0), group("default", "Again", 1)],
+            vec![
+                shortcut("s1", "A", "default"),
+                shortcut("s1", "B", "default"),
+            ],
+        );
+        let report = validate(&data);
+        assert_eq!(
+            codes(&data),
+            [
+                (Severity::Error, IssueCode::DuplicateGroupId),
+                (Severity::Error, IssueCode::DuplicateShortcutId),
+            ]
+        );
+        assert_eq!(report.issues[1].shortcut_id.as_deref(), Some("s1"));
+        assert_eq!(
+            report.error_summary(),
+            "Duplicate group id: default; Duplicate shortcut id: s1"
+        );
+    }
+
+    #[test]
+    fn dangling_group() {
+        let data = app_data(
+            vec![group("default", "Default", 0)],
+            vec![shortcut("s1", "A", "deleted")],
+        );
+        let report = validate(&data);
+        assert_eq!(codes(&data), [(Severity::Error, IssueCode::DanglingGroup)]);
+        assert_eq!(report.issues[0].group_id.as_deref(), Some("deleted"));
+    }
+
+    #[test]
+    fn empty_targets() {
+        let data = app_data(
+            vec![group("default", "Default", 0)],
+            vec![with_actions(
+                "s1",
+                vec![
+                    Action::Launch {
+                        path: " ".to_string(),
+                        args: None,
+                        window_config: None,
+                    },
+                    Action::Kill {
+                        process_name: String::new(),
+                    },
+                    Action::OpenFolder {
+                        path: String::new(),
+                        window_config: None,
+                    },
+                    Action::OpenUrl {
+                        url: String::new(),
+                        window_config: None,
+                    },
+                ],
+            )],
+        );
+        let report = validate(&data);
+        assert_eq!(codes(&data), [(Severity::Error, IssueCode::EmptyTarget); 4]);
+        let indexes: Vec<_> = report.issues.iter().map(|i| i.action_index).collect();
+        assert_eq!(indexes, [Some(0), Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn malformed_urls() {
+        let urls = ["example.com", "https://example.com/a b", "h:x", "1http://x"];
+        let data = app_data(
+            vec![group("default", "Default", 0)],
+            vec![with_actions(
+                "s1",
+                urls.iter()
+                    .map(|url| Action::OpenUrl {
+                        url: url.to_string(),
+                        window_config: None,
+                    })
+                    .collect(),
+            )],
+        );
+        assert_eq!(codes(&data), [(Severity::Error, IssueCode::InvalidUrl); 4]);
+    }
+
+    #[test]
+    fn delay_too_long() {
+        let data = app_data(
+            vec![group("default", "Default", 0)],
+            vec![with_actions(
+                "s1",
+                vec![Action::Delay {
+                    ms: MAX_DELAY_MS + 1,
+                }],
+            )],
+        );
+        assert_eq!(codes(&data), [(Severity::Error, IssueCode::DelayTooLong)]);
+    }
+
+    #[test]
+    fn missing_launch_target_is_a_warning() {
+        let data = app_data(
+            vec![group("default", "Default", 0)],
+            vec![with_actions(
+                "s1",
+                vec![
+                    Action::Launch {
+                        path: "no-such-program-for-validation".to_string(),
+                        args: None,
+                        window_config: None,
+                    },
+                    // 実行時と同じく、変数は展開しない
+                    Action::Launch {
+                        path: "${PATH}".to_string(),
+                        args: None,
+                        window_config: None,
+                    },
+                ],
+            )],
+        );
+        let report = validate(&data);
+        assert_eq!(
+            codes(&data),
+            [(Severity::Warning, IssueCode::MissingLaunchTarget); 2]
+        );
+        assert!(!report.has_errors());
+        assert!(report.issues[1]
+            .message
+            .ends_with("executable not found: ${PATH} (variables are not expanded)"));
+    }
+}
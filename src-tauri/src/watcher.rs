@@ -0,0 +1,106 @@
+use crate::validation::{self, ValidationReport};
+use crate::{data_dir, storage};
+use crate::{get_data_dir, get_data_path, AppData};
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use std::fs;
+use std::sync::mpsc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+// ========================================
+// Events
+// ========================================
+
+pub const APP_DATA_CHANGED_EVENT: &str = "app-data-changed";
+
+/// data.json が外部で変更されたときにフロントエンドへ送るイベント
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum AppDataChangedEvent {
+    /// The file was reloaded successfully.
+    Reloaded {
+        data: AppData,
+        validation: ValidationReport,
+    },
+    /// The new content could not be loaded (see `load_app_data`).
+    Failed { error: String },
+}
+
+// ========================================
+// Watcher
+// ========================================
+
+/// Editors and sync tools often write a file in several steps; wait until
+/// the events stop for this long before reloading.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// data.json の外部変更の監視を開始する。監視はアプリ終了まで専用スレッドで続く。
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        if let Err(e) = watch(&app) {
+            eprintln!("Failed to watch data file: {}", e);
+        }
+    });
+}
+
+fn watch(app: &AppHandle) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+    // ファイルではなくフォルダを監視する。data.json は置き換え（rename）で
+    // 保存されるため、ファイル自体の監視は最初の保存で外れてしまう。
+    // プロファイルを切り替えても監視し直さずに済むよう、データフォルダ全体を見る
+    watcher
+        .watch(&data_dir::location().path, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch data folder: {}", e))?;
+
+    let mut last_seen = fs::read(get_data_path())
+        .ok()
+        .map(|c| storage::fingerprint(&c));
+
+    while let Ok(event) = rx.recv() {
+        let data_dir = get_data_dir();
+        let touches_data = event.is_ok_and(|event| {
+            event.paths.iter().any(|path| {
+                path.parent() == Some(data_dir.as_path())
+                    && path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(storage::is_data_file_name)
+            })
+        });
+        if !touches_data {
+            continue;
+        }
+        while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+        // 書式の切り替えでファイル名が変わることがあるので毎回探し直す
+        let content = fs::read(get_data_path()).ok();
+        let current = content.as_deref().map(storage::fingerprint);
+        if current == last_seen {
+            continue;
+        }
+        last_seen = current;
+        if current.is_some_and(storage::is_own_write) {
+            continue;
+        }
+
+        let event = match storage::load_app_data() {
+            Ok(data) => {
+                let validation = validation::validate(&data);
+                AppDataChangedEvent::Reloaded { data, validation }
+            }
+            Err(error) => AppDataChangedEvent::Failed { error },
+        };
+        if let Err(e) = app.emit(APP_DATA_CHANGED_EVENT, &event) {
+            eprintln!("Failed to emit app data change: {}", e);
+        }
+    }
+    Ok(())
+}
@@ -1,13 +1,20 @@
-import { useState, useCallback } from "react";
+import { useState, useCallback, useEffect } from "react";
 import "./App.css";
 import { Sidebar, TitleBar, PageContainer } from "./components/layout";
 import {
   ShortcutsPage,
   GroupsPage,
+  TrashPage,
+  ProfilesPage,
   SettingsPage,
   AboutPage,
 } from "./components/pages";
 import { ShortcutEditor } from "./components/ShortcutEditor";
+import { ExecutionProgress } from "./components/ExecutionProgress";
+import { DataRecovery } from "./components/DataRecovery";
+import { BundleExportModal } from "./components/BundleExportModal";
+import { BundleImportModal } from "./components/BundleImportModal";
+import { ShareCodeImportModal } from "./components/ShareCodeImportModal";
 import { ConfirmDialog } from "./components/common";
 import { useAppData } from "./hooks/useAppData";
 import type { Shortcut, Group } from "./types";
@@ -17,6 +24,9 @@ function App() {
   const [editorOpen, setEditorOpen] = useState(false);
   const [editingShortcut, setEditingShortcut] = useState<Shortcut | null>(null);
   const [deleteConfirmOpen, setDeleteConfirmOpen] = useState(false);
+  const [exportOpen, setExportOpen] = useState(false);
+  const [importOpen, setImportOpen] = useState(false);
+  const [codeImportOpen, setCodeImportOpen] = useState(false);
   const [shortcutToDelete, setShortcutToDelete] = useState<Shortcut | null>(
     null,
   );
@@ -25,17 +35,71 @@ function App() {
     shortcuts,
     groups,
     loading,
+    loadError,
+    loadData,
+    listQuarantined,
+    restoreQuarantined,
+    resetData,
+    listBackups,
+    restoreBackup,
     addShortcut,
     updateShortcut,
-    updateShortcuts,
+    reorderShortcuts,
     deleteShortcut,
     addGroup,
     updateGroup,
     deleteGroup,
     executeShortcut,
-    saveGroups,
+    reorderGroups,
+    undoStatus,
+    undo,
+    redo,
+    listTrash,
+    restoreFromTrash,
+    purgeTrash,
+    exportBundle,
+    previewImport,
+    importBundle,
+    profiles,
+    createProfile,
+    duplicateProfile,
+    deleteProfile,
+    switchProfile,
+    getDataLocation,
+    getDataFormat,
+    setDataFormat,
+    createShareCode,
+    previewShareCode,
+    importShareCode,
   } = useAppData();
 
+  // Ctrl+Z / Ctrl+Y (Ctrl+Shift+Z) で元に戻す・やり直す。入力欄の中では
+  // テキストの取り消しを優先する
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (!(e.ctrlKey || e.metaKey) || editorOpen) return;
+      const target = e.target as HTMLElement;
+      if (
+        target.isContentEditable ||
+        ["INPUT", "TEXTAREA", "SELECT"].includes(target.tagName)
+      ) {
+        return;
+      }
+
+      const key = e.key.toLowerCase();
+      if (key === "z" && !e.shiftKey) {
+        e.preventDefault();
+        undo();
+      } else if (key === "y" || (key === "z" && e.shiftKey)) {
+        e.preventDefault();
+        redo();
+      }
+    };
+
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, [editorOpen, undo, redo]);
+
   const handleCreateShortcut = () => {
     setEditingShortcut(null);
     setEditorOpen(true);
@@ -77,21 +141,17 @@ function App() {
   );
 
   const handleReorderShortcuts = useCallback(
-    async (_groupId: string, newOrder: Shortcut[]) => {
-      const reorderedShortcuts = newOrder.map((s, index) => ({
-        ...s,
-        order: index,
-      }));
-      await updateShortcuts(reorderedShortcuts);
+    async (groupId: string, newOrder: Shortcut[]) => {
+      await reorderShortcuts(groupId, newOrder.map((s) => s.id));
     },
-    [updateShortcuts],
+    [reorderShortcuts],
   );
 
   const handleReorderGroups = useCallback(
     async (newGroups: Group[]) => {
-      await saveGroups(newGroups);
+      await reorderGroups(newGroups.map((g) => g.id));
     },
-    [saveGroups],
+    [reorderGroups],
   );
 
   const renderPage = () => {
@@ -106,6 +166,8 @@ function App() {
             onEdit={handleEditShortcut}
             onDelete={handleDeleteShortcut}
             onCreate={handleCreateShortcut}
+            onImportCode={() => setCodeImportOpen(true)}
+            onShare={(shortcut) => createShareCode(shortcut.id)}
             onToggleGroup={handleToggleGroup}
             onReorderShortcuts={handleReorderShortcuts}
           />
@@ -120,8 +182,39 @@ function App() {
             onReorder={handleReorderGroups}
           />
         );
+      case "trash":
+        return (
+          <TrashPage
+            shortcuts={shortcuts}
+            groups={groups}
+            undoStatus={undoStatus}
+            onUndo={undo}
+            onRedo={redo}
+            onList={listTrash}
+            onRestore={restoreFromTrash}
+            onPurge={purgeTrash}
+          />
+        );
+      case "profiles":
+        return (
+          <ProfilesPage
+            profiles={profiles}
+            onCreate={createProfile}
+            onDuplicate={duplicateProfile}
+            onSwitch={switchProfile}
+            onDelete={deleteProfile}
+          />
+        );
       case "settings":
-        return <SettingsPage />;
+        return (
+          <SettingsPage
+            onExportData={() => setExportOpen(true)}
+            onImportData={() => setImportOpen(true)}
+            onGetDataLocation={getDataLocation}
+            onGetDataFormat={getDataFormat}
+            onSetDataFormat={setDataFormat}
+          />
+        );
       case "about":
         return <AboutPage />;
       default:
@@ -133,10 +226,28 @@ function App() {
     <div className="flex flex-col h-full bg-[#1a1a1a]">
       <TitleBar />
       <div className="flex flex-1 overflow-hidden">
-        <Sidebar currentPage={currentPage} onPageChange={setCurrentPage} />
-        <PageContainer currentPage={currentPage}>{renderPage()}</PageContainer>
+        <Sidebar
+          currentPage={currentPage}
+          onPageChange={setCurrentPage}
+          profile={profiles.find((p) => p.active)?.name}
+        />
+        {loadError ? (
+          <DataRecovery
+            error={loadError}
+            onReload={loadData}
+            onListQuarantined={listQuarantined}
+            onRestore={restoreQuarantined}
+            onListBackups={listBackups}
+            onRestoreBackup={restoreBackup}
+            onReset={resetData}
+          />
+        ) : (
+          <PageContainer currentPage={currentPage}>{renderPage()}</PageContainer>
+        )}
       </div>
 
+      <ExecutionProgress />
+
       <ShortcutEditor
         isOpen={editorOpen}
         onClose={() => setEditorOpen(false)}
@@ -145,6 +256,29 @@ function App() {
         onSave={handleSaveShortcut}
       />
 
+      <BundleExportModal
+        isOpen={exportOpen}
+        onClose={() => setExportOpen(false)}
+        groups={groups}
+        shortcuts={shortcuts}
+        onExport={exportBundle}
+      />
+
+      <BundleImportModal
+        isOpen={importOpen}
+        onClose={() => setImportOpen(false)}
+        onPreview={previewImport}
+        onImport={importBundle}
+      />
+
+      <ShareCodeImportModal
+        isOpen={codeImportOpen}
+        onClose={() => setCodeImportOpen(false)}
+        groups={groups}
+        onPreview={previewShareCode}
+        onImport={importShareCode}
+      />
+
       <ConfirmDialog
         isOpen={deleteConfirmOpen}
         onClose={() => {
@@ -153,7 +287,7 @@ function App() {
         }}
         onConfirm={handleConfirmDelete}
         title="ショートカットを削除"
-        message={`「${shortcutToDelete?.name || ""}」を削除しますか？この操作は取り消せません。`}
+        message={`「${shortcutToDelete?.name || ""}」を削除しますか？削除したショートカットはゴミ箱から復元できます。`}
         confirmText="削除"
         cancelText="キャンセル"
         variant="danger"
@@ -0,0 +1,122 @@
+import { useEffect, useState } from "react";
+import { Download } from "lucide-react";
+import { save } from "@tauri-apps/plugin-dialog";
+import { Button, Modal, Toggle, IconDisplay } from "./common";
+import type {
+  ExportSelection,
+  ExportSummary,
+  Group,
+  Shortcut,
+} from "../types";
+
+interface BundleExportModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  groups: Group[];
+  shortcuts: Shortcut[];
+  onExport: (
+    path: string,
+    selection: ExportSelection,
+  ) => Promise<ExportSummary>;
+}
+
+/**
+ * 選択したグループ（と所属するショートカット）をバンドルファイルに書き出す
+ */
+export function BundleExportModal({
+  isOpen,
+  onClose,
+  groups,
+  shortcuts,
+  onExport,
+}: BundleExportModalProps) {
+  const [selected, setSelected] = useState<string[]>([]);
+  const [result, setResult] = useState<ExportSummary | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (isOpen) {
+      setSelected(groups.map((g) => g.id));
+      setResult(null);
+      setError(null);
+    }
+  }, [isOpen, groups]);
+
+  const sortedGroups = [...groups].sort((a, b) => a.order - b.order);
+
+  const toggle = (id: string, checked: boolean) => {
+    setSelected((current) =>
+      checked ? [...current, id] : current.filter((g) => g !== id),
+    );
+  };
+
+  const handleExport = async () => {
+    const path = await save({
+      defaultPath: "shortcuts.json",
+      filters: [
+        { name: "Shortcut Bundle (JSON)", extensions: ["json"] },
+        { name: "Shortcut Bundle (YAML)", extensions: ["yaml", "yml"] },
+        { name: "Shortcut Bundle (TOML)", extensions: ["toml"] },
+      ],
+    });
+    if (!path) return;
+
+    try {
+      setError(null);
+      setResult(await onExport(path, { groupIds: selected }));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title="データをエクスポート">
+      <div className="p-6 space-y-4">
+        <p className="text-sm text-gray-400">
+          書き出すグループを選択してください。グループ内のショートカットもすべて含まれます。
+        </p>
+
+        <div className="space-y-1">
+          {sortedGroups.map((group) => (
+            <div
+              key={group.id}
+              className="flex items-center gap-3 bg-white/5 rounded-md px-3 py-2"
+            >
+              <IconDisplay iconKey={group.icon} size={18} />
+              <span className="flex-1 text-sm text-white">{group.name}</span>
+              <span className="text-xs text-gray-500">
+                {shortcuts.filter((s) => s.groupId === group.id).length} 件
+              </span>
+              <Toggle
+                checked={selected.includes(group.id)}
+                onChange={(checked) => toggle(group.id, checked)}
+              />
+            </div>
+          ))}
+        </div>
+
+        {result && (
+          <p className="text-sm text-green-400">
+            ショートカット {result.shortcutCount} 件・グループ{" "}
+            {result.groupCount} 件を書き出しました
+          </p>
+        )}
+        {error && <p className="text-sm text-red-400">{error}</p>}
+
+        <div className="flex justify-end gap-2">
+          <Button variant="ghost" onClick={onClose}>
+            閉じる
+          </Button>
+          <Button
+            variant="primary"
+            disabled={selected.length === 0}
+            onClick={handleExport}
+          >
+            <Download className="w-4 h-4" />
+            エクスポート
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
@@ -0,0 +1,184 @@
+import { useEffect, useState } from "react";
+import { FileUp, Upload } from "lucide-react";
+import { open } from "@tauri-apps/plugin-dialog";
+import { Button, Modal, Select } from "./common";
+import type { ImportConflict, ImportOptions, ImportReport } from "../types";
+
+interface BundleImportModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  onPreview: (path: string, options: ImportOptions) => Promise<ImportReport>;
+  onImport: (
+    path: string,
+    options: ImportOptions,
+  ) => Promise<ImportReport | undefined>;
+}
+
+const RESOLUTION_LABELS: Record<ImportConflict["resolution"], string> = {
+  merged: "既存のグループに追加",
+  replaced: "置き換え",
+  skipped: "スキップ",
+  renamed: "名前を変えて追加",
+};
+
+/**
+ * バンドルファイルを選び、取り込んだ場合の結果を確認してから取り込む
+ */
+export function BundleImportModal({
+  isOpen,
+  onClose,
+  onPreview,
+  onImport,
+}: BundleImportModalProps) {
+  const [path, setPath] = useState<string | null>(null);
+  const [options, setOptions] = useState<Required<ImportOptions>>({
+    groups: "merge",
+    onConflict: "skip",
+  });
+  const [preview, setPreview] = useState<ImportReport | null>(null);
+  const [imported, setImported] = useState<ImportReport | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (isOpen) {
+      setPath(null);
+      setPreview(null);
+      setImported(null);
+      setError(null);
+    }
+  }, [isOpen]);
+
+  // ファイルや設定が変わるたびに結果を見積もり直す
+  useEffect(() => {
+    if (!path) return;
+    setError(null);
+    onPreview(path, options)
+      .then(setPreview)
+      .catch((err) => {
+        setPreview(null);
+        setError(err instanceof Error ? err.message : String(err));
+      });
+  }, [path, options, onPreview]);
+
+  const handleSelectFile = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [
+        {
+          name: "Shortcut Bundle",
+          extensions: ["json", "yaml", "yml", "toml"],
+        },
+      ],
+    });
+    if (typeof selected === "string") {
+      setImported(null);
+      setPath(selected);
+    }
+  };
+
+  const handleImport = async () => {
+    if (!path) return;
+    const report = await onImport(path, options);
+    if (report) {
+      setImported(report);
+      setPreview(null);
+    } else {
+      setError("取り込めませんでした。もう一度お試しください。");
+    }
+  };
+
+  const report = imported ?? preview;
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title="データをインポート">
+      <div className="p-6 space-y-4">
+        <div className="flex items-center gap-3">
+          <Button variant="secondary" onClick={handleSelectFile}>
+            <FileUp className="w-4 h-4" />
+            ファイルを選択
+          </Button>
+          <span className="text-sm text-gray-400 truncate" title={path ?? ""}>
+            {path ?? "バンドルファイル（.json / .yaml / .toml）を選択してください"}
+          </span>
+        </div>
+
+        <div className="grid grid-cols-2 gap-3">
+          <Select
+            label="同じ名前のグループ"
+            value={options.groups}
+            onChange={(e) =>
+              setOptions({
+                ...options,
+                groups: e.target.value as "merge" | "replace",
+              })
+            }
+            options={[
+              { value: "merge", label: "既存のグループに追加" },
+              { value: "replace", label: "グループの中身を置き換え" },
+            ]}
+          />
+          <Select
+            label="同じ名前のショートカット"
+            value={options.onConflict}
+            onChange={(e) =>
+              setOptions({
+                ...options,
+                onConflict: e.target.value as "skip" | "rename" | "replace",
+              })
+            }
+            options={[
+              { value: "skip", label: "スキップ" },
+              { value: "rename", label: "名前を変えて追加" },
+              { value: "replace", label: "上書き" },
+            ]}
+          />
+        </div>
+
+        {report && (
+          <div className="space-y-2">
+            <p className="text-sm text-white">
+              {imported ? "取り込みました: " : "取り込むと: "}
+              ショートカット {report.createdShortcuts} 件・グループ{" "}
+              {report.createdGroups} 件を追加
+              {report.replacedShortcuts > 0 &&
+                `、${report.replacedShortcuts} 件を上書き`}
+              {report.skippedShortcuts > 0 &&
+                `、${report.skippedShortcuts} 件をスキップ`}
+              {report.removedShortcuts > 0 &&
+                `、置き換えで ${report.removedShortcuts} 件をゴミ箱へ移動`}
+            </p>
+            {report.conflicts.length > 0 && (
+              <div className="space-y-1">
+                {report.conflicts.map((conflict, index) => (
+                  <p key={index} className="text-xs text-gray-400">
+                    {conflict.kind === "group" ? "グループ" : "ショートカット"}{" "}
+                    「{conflict.name}」
+                    {conflict.groupName && `（${conflict.groupName}）`}:{" "}
+                    {RESOLUTION_LABELS[conflict.resolution]}
+                    {conflict.newName && `「${conflict.newName}」`}
+                  </p>
+                ))}
+              </div>
+            )}
+          </div>
+        )}
+
+        {error && <p className="text-sm text-red-400">{error}</p>}
+
+        <div className="flex justify-end gap-2">
+          <Button variant="ghost" onClick={onClose}>
+            閉じる
+          </Button>
+          <Button
+            variant="primary"
+            disabled={!preview || !!imported}
+            onClick={handleImport}
+          >
+            <Upload className="w-4 h-4" />
+            インポート
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
@@ -0,0 +1,162 @@
+import { useEffect, useState } from "react";
+import { AlertTriangle, RefreshCw, RotateCcw } from "lucide-react";
+import { Button, ConfirmDialog } from "./common";
+import type { BackupInfo, QuarantinedFile } from "../types";
+
+// ========================================
+// Data Recovery Screen
+// ========================================
+
+interface DataRecoveryProps {
+  error: string;
+  onReload: () => Promise<void>;
+  onListQuarantined: () => Promise<QuarantinedFile[]>;
+  onRestore: (fileName: string) => Promise<void>;
+  onListBackups: () => Promise<BackupInfo[]>;
+  onRestoreBackup: (fileName: string) => Promise<void>;
+  onReset: () => Promise<void>;
+}
+
+/**
+ * data.json を読み込めなかったときに通常画面の代わりに表示する。
+ * 元のファイルは上書きされていないので、手で直して再読み込みするか、
+ * 退避したコピーを修復して復元できる。
+ */
+export function DataRecovery({
+  error,
+  onReload,
+  onListQuarantined,
+  onRestore,
+  onListBackups,
+  onRestoreBackup,
+  onReset,
+}: DataRecoveryProps) {
+  const [files, setFiles] = useState<QuarantinedFile[]>([]);
+  const [backups, setBackups] = useState<BackupInfo[]>([]);
+  const [actionError, setActionError] = useState<string | null>(null);
+  const [resetConfirmOpen, setResetConfirmOpen] = useState(false);
+
+  useEffect(() => {
+    onListQuarantined().then(setFiles).catch(console.error);
+    onListBackups().then(setBackups).catch(console.error);
+  }, [error, onListQuarantined, onListBackups]);
+
+  const run = async (action: () => Promise<void>) => {
+    try {
+      setActionError(null);
+      await action();
+    } catch (err) {
+      setActionError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  return (
+    <div className="flex-1 overflow-y-auto p-8">
+      <div className="max-w-2xl mx-auto space-y-6">
+        <div className="flex items-start gap-3">
+          <AlertTriangle className="w-6 h-6 text-yellow-400 shrink-0 mt-0.5" />
+          <div className="space-y-1">
+            <h1 className="text-lg font-semibold text-white">
+              データファイルを読み込めませんでした
+            </h1>
+            <p className="text-sm text-gray-400">
+              data.json
+              は上書きされていません。ファイルを修正して再読み込みするか、退避したコピーを修正して復元してください。
+            </p>
+          </div>
+        </div>
+
+        <pre className="text-xs text-red-300 bg-red-500/10 border border-red-500/20 rounded-md p-3 whitespace-pre-wrap break-all">
+          {error}
+        </pre>
+
+        {actionError && <p className="text-sm text-red-400">{actionError}</p>}
+
+        <div className="flex gap-2">
+          <Button variant="primary" onClick={() => run(onReload)}>
+            <RefreshCw className="w-4 h-4 mr-2" />
+            再読み込み
+          </Button>
+          <Button variant="danger" onClick={() => setResetConfirmOpen(true)}>
+            初期化して続行
+          </Button>
+        </div>
+
+        {files.length > 0 && (
+          <div className="space-y-2">
+            <h2 className="text-sm font-medium text-gray-300">退避したファイル</h2>
+            {files.map((file) => (
+              <div
+                key={file.fileName}
+                className="flex items-center gap-3 bg-white/5 rounded-md px-3 py-2"
+              >
+                <div className="flex-1 min-w-0">
+                  <p className="text-sm text-white truncate" title={file.path}>
+                    {file.fileName}
+                  </p>
+                  <p className="text-xs text-gray-500">
+                    {new Date(file.modifiedMs).toLocaleString()} ・{" "}
+                    {(file.size / 1024).toFixed(1)} KB
+                  </p>
+                </div>
+                <Button
+                  size="sm"
+                  variant="ghost"
+                  onClick={() => run(() => onRestore(file.fileName))}
+                >
+                  <RotateCcw className="w-3.5 h-3.5 mr-1.5" />
+                  復元
+                </Button>
+              </div>
+            ))}
+          </div>
+        )}
+
+        {backups.length > 0 && (
+          <div className="space-y-2">
+            <h2 className="text-sm font-medium text-gray-300">
+              自動バックアップ
+            </h2>
+            {backups.map((backup) => (
+              <div
+                key={backup.fileName}
+                className="flex items-center gap-3 bg-white/5 rounded-md px-3 py-2"
+              >
+                <div className="flex-1 min-w-0">
+                  <p className="text-sm text-white truncate" title={backup.path}>
+                    {new Date(backup.createdMs).toLocaleString()}
+                  </p>
+                  <p className="text-xs text-gray-500">
+                    {backup.shortcutCount != null
+                      ? `ショートカット ${backup.shortcutCount} 件・グループ ${backup.groupCount} 件`
+                      : "読み込めないバックアップ"}
+                  </p>
+                </div>
+                <Button
+                  size="sm"
+                  variant="ghost"
+                  disabled={backup.shortcutCount == null}
+                  onClick={() => run(() => onRestoreBackup(backup.fileName))}
+                >
+                  <RotateCcw className="w-3.5 h-3.5 mr-1.5" />
+                  復元
+                </Button>
+              </div>
+            ))}
+          </div>
+        )}
+      </div>
+
+      <ConfirmDialog
+        isOpen={resetConfirmOpen}
+        onClose={() => setResetConfirmOpen(false)}
+        onConfirm={() => run(onReset)}
+        title="データを初期化"
+        message="壊れた data.json を退避し、初期データで起動します。退避したファイルは後から復元できます。"
+        confirmText="初期化"
+        cancelText="キャンセル"
+        variant="danger"
+      />
+    </div>
+  );
+}
@@ -0,0 +1,128 @@
+import { motion, AnimatePresence } from "framer-motion";
+import { Check, X, Minus, Square } from "lucide-react";
+import { Spinner } from "./common";
+import {
+  useExecutionProgress,
+  type RunProgress,
+} from "../hooks/useExecutionProgress";
+import type { ActionResult, RunStatus } from "../types";
+
+// ========================================
+// Execution Progress Panel
+// ========================================
+
+const RUN_STATUS_LABELS: Record<RunStatus, string> = {
+  succeeded: "完了",
+  failed: "一部失敗",
+  aborted: "中断",
+  cancelled: "キャンセル",
+};
+
+export function ExecutionProgress() {
+  const { runs, cancelRun } = useExecutionProgress();
+
+  return (
+    <div className="fixed bottom-4 right-4 z-40 w-80 space-y-2 pointer-events-none">
+      <AnimatePresence>
+        {runs.map((run) => (
+          <motion.div
+            key={run.runId}
+            initial={{ opacity: 0, y: 12 }}
+            animate={{ opacity: 1, y: 0 }}
+            exit={{ opacity: 0, y: 12 }}
+            transition={{ duration: 0.15 }}
+            className="pointer-events-auto bg-[#2b2b2b] border border-white/10 rounded-lg shadow-xl p-3"
+          >
+            <RunProgressCard run={run} onCancel={() => cancelRun(run.runId)} />
+          </motion.div>
+        ))}
+      </AnimatePresence>
+    </div>
+  );
+}
+
+interface RunProgressCardProps {
+  run: RunProgress;
+  onCancel: () => void;
+}
+
+function RunProgressCard({ run, onCancel }: RunProgressCardProps) {
+  const isRunning = run.status === null;
+
+  return (
+    <div className="space-y-2">
+      <div className="flex items-center gap-2">
+        <span className="flex-1 text-sm font-medium text-white truncate">
+          {run.shortcutName}
+        </span>
+        {isRunning ? (
+          <button
+            onClick={onCancel}
+            className="p-1 rounded hover:bg-red-500/20 text-gray-400 hover:text-red-400 transition-colors"
+            title="キャンセル"
+          >
+            <Square className="w-3.5 h-3.5" />
+          </button>
+        ) : (
+          <span
+            className={`text-xs ${
+              run.status === "succeeded" ? "text-green-400" : "text-red-400"
+            }`}
+          >
+            {RUN_STATUS_LABELS[run.status!]}
+          </span>
+        )}
+      </div>
+
+      <div className="flex gap-1">
+        {Array.from({ length: run.actionCount }, (_, index) => (
+          <ActionStep
+            key={index}
+            result={run.results.find((r) => r.index === index)}
+            isActive={run.currentIndex === index}
+          />
+        ))}
+      </div>
+    </div>
+  );
+}
+
+function ActionStep({
+  result,
+  isActive,
+}: {
+  result?: ActionResult;
+  isActive: boolean;
+}) {
+  if (isActive) {
+    return (
+      <div className="flex-1 h-6 flex items-center justify-center rounded bg-[#0078d4]/20">
+        <Spinner size="sm" />
+      </div>
+    );
+  }
+
+  if (!result) {
+    return <div className="flex-1 h-6 rounded bg-white/5" />;
+  }
+
+  const styles = {
+    succeeded: "bg-green-500/20 text-green-400",
+    failed: "bg-red-500/20 text-red-400",
+    skipped: "bg-white/5 text-gray-500",
+  };
+  const icons = {
+    succeeded: <Check className="w-3.5 h-3.5" />,
+    failed: <X className="w-3.5 h-3.5" />,
+    skipped: <Minus className="w-3.5 h-3.5" />,
+  };
+
+  return (
+    <div
+      className={`flex-1 h-6 flex items-center justify-center rounded ${styles[result.status]}`}
+      title={`${result.actionType}: ${result.message} (${result.durationMs}ms)`}
+    >
+      {icons[result.status]}
+    </div>
+  );
+}
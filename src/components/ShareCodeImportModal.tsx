@@ -0,0 +1,110 @@
+import { useEffect, useState } from "react";
+import { ClipboardPaste } from "lucide-react";
+import { Button, Modal, Select, IconDisplay } from "./common";
+import type { Group, SharedShortcut } from "../types";
+
+interface ShareCodeImportModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  groups: Group[];
+  onPreview: (code: string) => Promise<SharedShortcut>;
+  onImport: (code: string, groupId: string) => Promise<string | undefined>;
+}
+
+/**
+ * 共有コードを貼り付けて、中身を確認してからショートカットとして追加する
+ */
+export function ShareCodeImportModal({
+  isOpen,
+  onClose,
+  groups,
+  onPreview,
+  onImport,
+}: ShareCodeImportModalProps) {
+  const [code, setCode] = useState("");
+  const [groupId, setGroupId] = useState("default");
+  const [preview, setPreview] = useState<SharedShortcut | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (isOpen) {
+      setCode("");
+      setGroupId("default");
+      setPreview(null);
+      setError(null);
+    }
+  }, [isOpen]);
+
+  // 貼り付けたコードをその場で読み取り、壊れていれば理由を表示する
+  useEffect(() => {
+    setPreview(null);
+    setError(null);
+    if (!code.trim()) return;
+    onPreview(code)
+      .then(setPreview)
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : String(err)),
+      );
+  }, [code, onPreview]);
+
+  const handleImport = async () => {
+    const id = await onImport(code, groupId);
+    if (id) {
+      onClose();
+    } else {
+      setError("追加できませんでした。もう一度お試しください。");
+    }
+  };
+
+  const sortedGroups = [...groups].sort((a, b) => a.order - b.order);
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title="コードから追加">
+      <div className="p-6 space-y-4">
+        <div className="space-y-1.5">
+          <label className="block text-sm font-medium text-gray-300">
+            共有コード
+          </label>
+          <textarea
+            value={code}
+            onChange={(e) => setCode(e.target.value)}
+            rows={4}
+            placeholder="asc1.…"
+            className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-md text-white text-xs font-mono placeholder-gray-500 focus:outline-none focus:border-[#0078d4] focus:ring-1 focus:ring-[#0078d4] transition-colors resize-none break-all"
+          />
+        </div>
+
+        <Select
+          label="追加先のグループ"
+          value={groupId}
+          onChange={(e) => setGroupId(e.target.value)}
+          options={sortedGroups.map((g) => ({ value: g.id, label: g.name }))}
+        />
+
+        {preview && (
+          <div className="flex items-center gap-3 bg-white/5 rounded-md px-3 py-2">
+            <IconDisplay iconKey={preview.icon} size={20} />
+            <span className="flex-1 text-sm text-white truncate">
+              {preview.name}
+            </span>
+            <span className="text-xs text-gray-500">
+              {preview.actions.length} アクション
+            </span>
+          </div>
+        )}
+
+        {error && <p className="text-sm text-red-400">{error}</p>}
+
+        <div className="flex justify-end gap-2">
+          <Button variant="ghost" onClick={onClose}>
+            閉じる
+          </Button>
+          <Button variant="primary" disabled={!preview} onClick={handleImport}>
+            <ClipboardPaste className="w-4 h-4" />
+            追加
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
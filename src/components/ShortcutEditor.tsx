@@ -32,6 +32,7 @@ import {
   Card,
   IconDisplay,
   IconPickerGrid,
+  Toggle,
 } from "./common";
 
 // ========================================
@@ -70,6 +71,7 @@ export function ShortcutEditor({
   const [name, setName] = useState("");
   const [icon, setIcon] = useState("zap");
   const [groupId, setGroupId] = useState("default");
+  const [linkRunnable, setLinkRunnable] = useState(false);
   // Use internal type with stable IDs for Reorder
   const [actionItems, setActionItems] = useState<
     { id: string; action: Action }[]
@@ -83,6 +85,7 @@ export function ShortcutEditor({
         setName(shortcut.name);
         setIcon(shortcut.icon);
         setGroupId(shortcut.groupId);
+        setLinkRunnable(shortcut.linkRunnable ?? false);
         setActionItems(
           shortcut.actions.map((action) => ({
             id: crypto.randomUUID(),
@@ -93,6 +96,7 @@ export function ShortcutEditor({
         setName("");
         setIcon("zap");
         setGroupId("default");
+        setLinkRunnable(false);
         setActionItems([]);
       }
     }
@@ -107,6 +111,8 @@ export function ShortcutEditor({
       icon,
       groupId,
       actions: actionItems.map((item) => item.action),
+      onError: shortcut?.onError,
+      linkRunnable,
       order: shortcut?.order || 0,
       createdAt: shortcut?.createdAt || new Date().toISOString(),
       updatedAt: new Date().toISOString(),
@@ -242,6 +248,19 @@ export function ShortcutEditor({
           )}
         </div>
 
+        {/* Link */}
+        <div className="space-y-1">
+          <Toggle
+            checked={linkRunnable}
+            onChange={setLinkRunnable}
+            label="リンクから確認なしで実行を許可"
+          />
+          <p className="text-xs text-gray-500">
+            advanced-shortcut://run/{shortcut?.id ?? "<ID または名前>"}
+            のリンクをブラウザや文書から開くと実行されます。オフのときは実行前に確認します
+          </p>
+        </div>
+
         {/* Footer */}
         <div className="flex justify-end gap-3 pt-4 border-t border-white/10">
           <Button variant="ghost" onClick={onClose}>
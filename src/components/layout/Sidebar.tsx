@@ -3,6 +3,8 @@ import {
   Zap,
   FolderOpen,
   Settings,
+  Trash2,
+  Layers,
   Info,
   ChevronLeft,
   Menu,
@@ -12,16 +14,23 @@ import { useState } from "react";
 interface SidebarProps {
   currentPage: string;
   onPageChange: (page: string) => void;
+  profile?: string;
 }
 
 const navItems = [
   { id: "shortcuts", label: "ショートカット", icon: Zap },
   { id: "groups", label: "グループ管理", icon: FolderOpen },
+  { id: "trash", label: "ゴミ箱", icon: Trash2 },
+  { id: "profiles", label: "プロファイル", icon: Layers },
   { id: "settings", label: "設定", icon: Settings },
   { id: "about", label: "このアプリについて", icon: Info },
 ];
 
-export function Sidebar({ currentPage, onPageChange }: SidebarProps) {
+export function Sidebar({
+  currentPage,
+  onPageChange,
+  profile,
+}: SidebarProps) {
   const [isCollapsed, setIsCollapsed] = useState(false);
 
   return (
@@ -104,6 +113,14 @@ export function Sidebar({ currentPage, onPageChange }: SidebarProps) {
           animate={{ opacity: 1 }}
           className="p-4 border-t border-white/5"
         >
+          {profile && (
+            <p
+              className="text-xs text-gray-400 text-center truncate mb-1"
+              title="使用中のプロファイル"
+            >
+              {profile}
+            </p>
+          )}
           <p className="text-xs text-gray-500 text-center">v0.6.1</p>
         </motion.div>
       )}
@@ -0,0 +1,194 @@
+import { useState } from "react";
+import { Layers, Plus, Copy, Trash2, Check } from "lucide-react";
+import type { ProfileInfo } from "../../types";
+import {
+  Button,
+  Card,
+  Input,
+  Modal,
+  SectionHeader,
+  ConfirmDialog,
+} from "../common";
+
+interface ProfilesPageProps {
+  profiles: ProfileInfo[];
+  onCreate: (name: string) => Promise<void>;
+  onDuplicate: (source: string, name: string) => Promise<void>;
+  onSwitch: (name: string) => Promise<void>;
+  onDelete: (name: string) => Promise<void>;
+}
+
+// 名前を入力するダイアログの用途（複製の場合は複製元）
+type NameDialog = { mode: "create" } | { mode: "duplicate"; source: string };
+
+export function ProfilesPage({
+  profiles,
+  onCreate,
+  onDuplicate,
+  onSwitch,
+  onDelete,
+}: ProfilesPageProps) {
+  const [nameDialog, setNameDialog] = useState<NameDialog | null>(null);
+  const [name, setName] = useState("");
+  const [toDelete, setToDelete] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const run = async (action: () => Promise<void>) => {
+    try {
+      setError(null);
+      await action();
+      return true;
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+      return false;
+    }
+  };
+
+  const openNameDialog = (dialog: NameDialog) => {
+    setName(dialog.mode === "duplicate" ? `${dialog.source} のコピー` : "");
+    setError(null);
+    setNameDialog(dialog);
+  };
+
+  const handleSubmitName = async () => {
+    if (!nameDialog) return;
+    const trimmed = name.trim();
+    const ok = await run(() =>
+      nameDialog.mode === "create"
+        ? onCreate(trimmed)
+        : onDuplicate(nameDialog.source, trimmed),
+    );
+    if (ok) setNameDialog(null);
+  };
+
+  return (
+    <div className="space-y-6">
+      <SectionHeader
+        title="プロファイル"
+        description="仕事用・自宅用など、ショートカットとグループの組を切り替えて使えます"
+        action={
+          <Button
+            variant="primary"
+            onClick={() => openNameDialog({ mode: "create" })}
+          >
+            <Plus className="w-4 h-4" />
+            新規作成
+          </Button>
+        }
+      />
+
+      {error && !nameDialog && <p className="text-sm text-red-400">{error}</p>}
+
+      <div className="space-y-2">
+        {profiles.map((profile) => (
+          <Card key={profile.name} hover={false}>
+            <div className="flex items-center gap-4 p-4">
+              <div
+                className={`shrink-0 w-10 h-10 flex items-center justify-center rounded-lg ${
+                  profile.active
+                    ? "bg-[#0078d4]/20 text-[#0078d4]"
+                    : "bg-white/10 text-gray-400"
+                }`}
+              >
+                <Layers className="w-5 h-5" />
+              </div>
+
+              <div className="flex-1 min-w-0">
+                <h3 className="font-medium text-white truncate">
+                  {profile.name}
+                  {profile.active && (
+                    <span className="ml-2 text-xs text-[#0078d4]">使用中</span>
+                  )}
+                </h3>
+                <p
+                  className="text-xs text-gray-500 truncate"
+                  title={profile.path}
+                >
+                  {profile.path}
+                </p>
+              </div>
+
+              <div className="flex items-center gap-1">
+                <Button
+                  size="sm"
+                  variant="ghost"
+                  disabled={profile.active}
+                  onClick={() => run(() => onSwitch(profile.name))}
+                >
+                  <Check className="w-3.5 h-3.5 mr-1.5" />
+                  切り替え
+                </Button>
+                <button
+                  onClick={() =>
+                    openNameDialog({ mode: "duplicate", source: profile.name })
+                  }
+                  title="複製"
+                  className="p-2 rounded-lg hover:bg-white/10 text-gray-400 hover:text-white transition-colors"
+                >
+                  <Copy className="w-4 h-4" />
+                </button>
+                <button
+                  onClick={() => setToDelete(profile.name)}
+                  disabled={profile.active || profile.name === "default"}
+                  title="削除"
+                  className="p-2 rounded-lg hover:bg-red-500/20 text-gray-400 hover:text-red-400 transition-colors disabled:opacity-30 disabled:pointer-events-none"
+                >
+                  <Trash2 className="w-4 h-4" />
+                </button>
+              </div>
+            </div>
+          </Card>
+        ))}
+      </div>
+
+      <Modal
+        isOpen={nameDialog !== null}
+        onClose={() => setNameDialog(null)}
+        title={
+          nameDialog?.mode === "duplicate"
+            ? `「${nameDialog.source}」を複製`
+            : "プロファイルを作成"
+        }
+        size="sm"
+      >
+        <div className="p-6 space-y-4">
+          <Input
+            label="プロファイル名"
+            value={name}
+            autoFocus
+            onChange={(e) => setName(e.target.value)}
+            onKeyDown={(e) => {
+              if (e.key === "Enter") handleSubmitName();
+            }}
+            error={error ?? undefined}
+          />
+          <div className="flex justify-end gap-2">
+            <Button variant="ghost" onClick={() => setNameDialog(null)}>
+              キャンセル
+            </Button>
+            <Button
+              variant="primary"
+              disabled={!name.trim()}
+              onClick={handleSubmitName}
+            >
+              {nameDialog?.mode === "duplicate" ? "複製" : "作成"}
+            </Button>
+          </div>
+        </div>
+      </Modal>
+
+      <ConfirmDialog
+        isOpen={toDelete !== null}
+        onClose={() => setToDelete(null)}
+        onConfirm={() => {
+          if (toDelete) run(() => onDelete(toDelete));
+        }}
+        title="プロファイルを削除"
+        message={`「${toDelete ?? ""}」のショートカット・グループ・ゴミ箱・バックアップをすべて削除します。この操作は取り消せません。`}
+        confirmText="削除"
+        cancelText="キャンセル"
+        variant="danger"
+      />
+    </div>
+  );
+}
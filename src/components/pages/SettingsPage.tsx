@@ -1,4 +1,11 @@
-import { SectionHeader, Card, Toggle, Button, Spinner } from "../common";
+import {
+  SectionHeader,
+  Card,
+  Toggle,
+  Button,
+  Spinner,
+  Select,
+} from "../common";
 import {
   Monitor,
   Moon,
@@ -9,11 +16,33 @@ import {
   AlertCircle,
   ArrowDownCircle,
 } from "lucide-react";
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { motion, AnimatePresence } from "framer-motion";
 import { useUpdater } from "../../hooks/useUpdater";
+import type { DataFormat, DataLocation } from "../../types";
+
+const DATA_SOURCE_LABELS: Record<DataLocation["source"], string> = {
+  cli: "--data-dir で指定",
+  env: "環境変数 ADVANCED_SHORTCUT_DATA_DIR で指定",
+  portable: "ポータブルモード",
+  default: "既定の場所",
+};
+
+interface SettingsPageProps {
+  onExportData: () => void;
+  onImportData: () => void;
+  onGetDataLocation: () => Promise<DataLocation>;
+  onGetDataFormat: () => Promise<DataFormat>;
+  onSetDataFormat: (format: DataFormat) => Promise<string>;
+}
 
-export function SettingsPage() {
+export function SettingsPage({
+  onExportData,
+  onImportData,
+  onGetDataLocation,
+  onGetDataFormat,
+  onSetDataFormat,
+}: SettingsPageProps) {
   const [settings, setSettings] = useState({
     darkMode: true,
     notifications: true,
@@ -21,6 +50,31 @@ export function SettingsPage() {
     runAtStartup: false,
   });
   const [updateExpanded, setUpdateExpanded] = useState(false);
+  const [dataLocation, setDataLocation] = useState<DataLocation | null>(
+    null,
+  );
+  const [dataFormat, setDataFormat] = useState<DataFormat | null>(null);
+  const [dataFormatError, setDataFormatError] = useState<string | null>(
+    null,
+  );
+
+  useEffect(() => {
+    onGetDataLocation().then(setDataLocation).catch(console.error);
+  }, [onGetDataLocation]);
+
+  useEffect(() => {
+    onGetDataFormat().then(setDataFormat).catch(console.error);
+  }, [onGetDataFormat]);
+
+  const handleDataFormatChange = async (format: DataFormat) => {
+    try {
+      setDataFormatError(null);
+      await onSetDataFormat(format);
+      setDataFormat(format);
+    } catch (err) {
+      setDataFormatError(err instanceof Error ? err.message : String(err));
+    }
+  };
 
   const {
     status: updateStatus,
@@ -274,16 +328,61 @@ export function SettingsPage() {
       <Card hover={false} className="p-6">
         <h3 className="text-lg font-semibold text-white mb-4">データ管理</h3>
         <div className="space-y-3">
-          <button className="w-full text-left px-4 py-3 rounded-lg bg-white/5 hover:bg-white/10 transition-colors">
+          {dataLocation && (
+            <div className="px-4 py-3 rounded-lg bg-white/5">
+              <span className="text-white">保存先</span>
+              <p
+                className="text-sm text-gray-400 font-mono break-all"
+                title={dataLocation.path}
+              >
+                {dataLocation.path}
+              </p>
+              <p className="text-xs text-gray-500">
+                {DATA_SOURCE_LABELS[dataLocation.source]}
+              </p>
+            </div>
+          )}
+          <div className="flex items-center justify-between gap-4 px-4 py-3 rounded-lg bg-white/5">
+            <div>
+              <span className="text-white">データファイルの形式</span>
+              <p className="text-sm text-gray-500">
+                YAML や TOML にするとテキストエディタで編集しやすくなります
+              </p>
+              {dataFormatError && (
+                <p className="text-sm text-red-400">{dataFormatError}</p>
+              )}
+            </div>
+            <div className="w-32 shrink-0">
+              <Select
+                value={dataFormat ?? "json"}
+                disabled={!dataFormat}
+                onChange={(e) =>
+                  handleDataFormatChange(e.target.value as DataFormat)
+                }
+                options={[
+                  { value: "json", label: "JSON" },
+                  { value: "yaml", label: "YAML" },
+                  { value: "toml", label: "TOML" },
+                ]}
+              />
+            </div>
+          </div>
+          <button
+            onClick={onExportData}
+            className="w-full text-left px-4 py-3 rounded-lg bg-white/5 hover:bg-white/10 transition-colors"
+          >
             <span className="text-white">データをエクスポート</span>
             <p className="text-sm text-gray-500">
-              ショートカットとグループをJSONファイルに保存
+              ショートカットとグループを別の環境に持ち出せるファイルに保存
             </p>
           </button>
-          <button className="w-full text-left px-4 py-3 rounded-lg bg-white/5 hover:bg-white/10 transition-colors">
+          <button
+            onClick={onImportData}
+            className="w-full text-left px-4 py-3 rounded-lg bg-white/5 hover:bg-white/10 transition-colors"
+          >
             <span className="text-white">データをインポート</span>
             <p className="text-sm text-gray-500">
-              JSONファイルからデータを読み込み
+              エクスポートしたファイルからショートカットとグループを追加
             </p>
           </button>
           <button className="w-full text-left px-4 py-3 rounded-lg bg-red-500/10 hover:bg-red-500/20 transition-colors">
@@ -8,9 +8,12 @@ import {
   Zap,
   GripVertical,
   ExternalLink,
+  Share2,
+  Check,
+  ClipboardPaste,
 } from "lucide-react";
 import { useState, useEffect } from "react";
-import type { Shortcut, Group } from "../../types";
+import type { Shortcut, Group, RunReport } from "../../types";
 import {
   Button,
   Card,
@@ -25,10 +28,12 @@ interface ShortcutsPageProps {
   shortcuts: Shortcut[];
   groups: Group[];
   loading: boolean;
-  onExecute: (shortcut: Shortcut) => Promise<string[]>;
+  onExecute: (shortcut: Shortcut) => Promise<RunReport>;
   onEdit: (shortcut: Shortcut) => void;
   onDelete: (shortcut: Shortcut) => void;
   onCreate: () => void;
+  onImportCode: () => void;
+  onShare: (shortcut: Shortcut) => Promise<string>;
   onToggleGroup: (groupId: string) => void;
   onReorderShortcuts: (groupId: string, shortcuts: Shortcut[]) => void;
 }
@@ -41,6 +46,8 @@ export function ShortcutsPage({
   onEdit,
   onDelete,
   onCreate,
+  onImportCode,
+  onShare,
   onToggleGroup,
   onReorderShortcuts,
 }: ShortcutsPageProps) {
@@ -53,13 +60,17 @@ export function ShortcutsPage({
     useState<Shortcut | null>(null);
   const [isDesktopShortcutModalOpen, setIsDesktopShortcutModalOpen] =
     useState(false);
+  const [copiedId, setCopiedId] = useState<string | null>(null);
 
   const handleExecute = async (shortcut: Shortcut) => {
     setExecutingId(shortcut.id);
     setExecutionResult(null);
     try {
-      await onExecute(shortcut);
-      setExecutionResult({ id: shortcut.id, success: true });
+      const report = await onExecute(shortcut);
+      setExecutionResult({
+        id: shortcut.id,
+        success: report.status === "succeeded",
+      });
     } catch {
       setExecutionResult({ id: shortcut.id, success: false });
     } finally {
@@ -68,6 +79,18 @@ export function ShortcutsPage({
     }
   };
 
+  // 共有コードをクリップボードにコピーし、少しの間チェックマークを表示する
+  const handleShare = async (shortcut: Shortcut) => {
+    try {
+      const code = await onShare(shortcut);
+      await navigator.clipboard.writeText(code);
+      setCopiedId(shortcut.id);
+      setTimeout(() => setCopiedId(null), 2000);
+    } catch (err) {
+      console.error("Failed to copy share code:", err);
+    }
+  };
+
   if (loading) {
     return (
       <div className="flex items-center justify-center h-64">
@@ -84,10 +107,16 @@ export function ShortcutsPage({
         title="ショートカット"
         description="ワンクリックで複数のアプリを起動したり、プロセスを終了したりできます"
         action={
-          <Button variant="primary" onClick={onCreate}>
-            <Plus className="w-4 h-4" />
-            新規作成
-          </Button>
+          <div className="flex gap-2">
+            <Button variant="secondary" onClick={onImportCode}>
+              <ClipboardPaste className="w-4 h-4" />
+              コードから追加
+            </Button>
+            <Button variant="primary" onClick={onCreate}>
+              <Plus className="w-4 h-4" />
+              新規作成
+            </Button>
+          </div>
         }
       />
 
@@ -119,10 +148,12 @@ export function ShortcutsPage({
                 shortcuts={groupShortcuts}
                 executingId={executingId}
                 executionResult={executionResult}
+                copiedId={copiedId}
                 onToggle={() => onToggleGroup(group.id)}
                 onExecute={handleExecute}
                 onEdit={onEdit}
                 onDelete={onDelete}
+                onShare={handleShare}
                 onCreateDesktopShortcut={(shortcut) => {
                   setDesktopShortcutModal(shortcut);
                   setIsDesktopShortcutModalOpen(true);
@@ -155,10 +186,12 @@ interface GroupSectionProps {
   shortcuts: Shortcut[];
   executingId: string | null;
   executionResult: { id: string; success: boolean } | null;
+  copiedId: string | null;
   onToggle: () => void;
   onExecute: (shortcut: Shortcut) => void;
   onEdit: (shortcut: Shortcut) => void;
   onDelete: (shortcut: Shortcut) => void;
+  onShare: (shortcut: Shortcut) => void;
   onCreateDesktopShortcut: (shortcut: Shortcut) => void;
   onReorder: (shortcuts: Shortcut[]) => void;
 }
@@ -171,10 +204,12 @@ function GroupSection({
   shortcuts,
   executingId,
   executionResult,
+  copiedId,
   onToggle,
   onExecute,
   onEdit,
   onDelete,
+  onShare,
   onCreateDesktopShortcut,
   onReorder,
 }: GroupSectionProps) {
@@ -230,9 +265,11 @@ function GroupSection({
                     ? executionResult.success
                     : null
                 }
+                isCopied={copiedId === item.shortcut.id}
                 onExecute={() => onExecute(item.shortcut)}
                 onEdit={() => onEdit(item.shortcut)}
                 onDelete={() => onDelete(item.shortcut)}
+                onShare={() => onShare(item.shortcut)}
                 onCreateDesktopShortcut={() =>
                   onCreateDesktopShortcut(item.shortcut)
                 }
@@ -253,9 +290,11 @@ interface ShortcutItemProps {
   item: ShortcutWrapper;
   isExecuting: boolean;
   executionResult: boolean | null;
+  isCopied: boolean;
   onExecute: () => void;
   onEdit: () => void;
   onDelete: () => void;
+  onShare: () => void;
   onCreateDesktopShortcut: () => void;
 }
 
@@ -263,9 +302,11 @@ function ShortcutItem({
   item,
   isExecuting,
   executionResult,
+  isCopied,
   onExecute,
   onEdit,
   onDelete,
+  onShare,
   onCreateDesktopShortcut,
 }: ShortcutItemProps) {
   const { shortcut } = item;
@@ -322,6 +363,18 @@ function ShortcutItem({
             <ExternalLink className="w-4 h-4" />
           </button>
 
+          <button
+            onClick={onShare}
+            className="p-2 rounded-lg hover:bg-white/10 text-gray-400 hover:text-white transition-colors"
+            title={isCopied ? "共有コードをコピーしました" : "共有コードをコピー"}
+          >
+            {isCopied ? (
+              <Check className="w-4 h-4 text-green-400" />
+            ) : (
+              <Share2 className="w-4 h-4" />
+            )}
+          </button>
+
           <button
             onClick={onEdit}
             className="p-2 rounded-lg hover:bg-white/10 text-gray-400 hover:text-white transition-colors"
@@ -0,0 +1,196 @@
+import { useCallback, useEffect, useState } from "react";
+import { Trash2, RotateCcw, Undo2, Redo2 } from "lucide-react";
+import type {
+  ChangeKind,
+  Group,
+  Shortcut,
+  TrashEntry,
+  UndoStatus,
+} from "../../types";
+import {
+  Button,
+  Card,
+  SectionHeader,
+  EmptyState,
+  IconDisplay,
+  ConfirmDialog,
+} from "../common";
+
+const CHANGE_LABELS: Record<ChangeKind, string> = {
+  create_shortcut: "ショートカットの作成",
+  update_shortcut: "ショートカットの編集",
+  delete_shortcut: "ショートカットの削除",
+  move_shortcut: "ショートカットの移動",
+  reorder_shortcuts: "ショートカットの並べ替え",
+  create_group: "グループの作成",
+  update_group: "グループの編集",
+  delete_group: "グループの削除",
+  move_group: "グループの移動",
+  reorder_groups: "グループの並べ替え",
+  restore_from_trash: "ゴミ箱からの復元",
+  import_bundle: "インポート",
+  save: "保存",
+};
+
+interface TrashPageProps {
+  shortcuts: Shortcut[];
+  groups: Group[];
+  undoStatus: UndoStatus;
+  onUndo: () => Promise<void>;
+  onRedo: () => Promise<void>;
+  onList: () => Promise<TrashEntry[]>;
+  onRestore: (entryId: string) => Promise<string | undefined>;
+  onPurge: (entryId?: string) => Promise<number>;
+}
+
+export function TrashPage({
+  shortcuts,
+  groups,
+  undoStatus,
+  onUndo,
+  onRedo,
+  onList,
+  onRestore,
+  onPurge,
+}: TrashPageProps) {
+  const [entries, setEntries] = useState<TrashEntry[]>([]);
+  const [emptyConfirmOpen, setEmptyConfirmOpen] = useState(false);
+
+  const reload = useCallback(() => {
+    onList().then(setEntries).catch(console.error);
+  }, [onList]);
+
+  // 元に戻す・やり直しでもゴミ箱の中身が変わるので、データが変わるたびに読み直す
+  useEffect(() => {
+    reload();
+  }, [reload, shortcuts, groups]);
+
+  const handlePurge = async (entryId?: string) => {
+    await onPurge(entryId);
+    reload();
+  };
+
+  return (
+    <div className="space-y-6">
+      <SectionHeader
+        title="ゴミ箱"
+        description="削除したショートカットとグループは30日間ここから復元できます"
+        action={
+          <div className="flex gap-2">
+            <Button
+              variant="secondary"
+              disabled={!undoStatus.undo}
+              onClick={onUndo}
+              title={
+                undoStatus.undo
+                  ? `${CHANGE_LABELS[undoStatus.undo]}を元に戻す (Ctrl+Z)`
+                  : undefined
+              }
+            >
+              <Undo2 className="w-4 h-4" />
+              元に戻す
+            </Button>
+            <Button
+              variant="secondary"
+              disabled={!undoStatus.redo}
+              onClick={onRedo}
+              title={
+                undoStatus.redo
+                  ? `${CHANGE_LABELS[undoStatus.redo]}をやり直す (Ctrl+Y)`
+                  : undefined
+              }
+            >
+              <Redo2 className="w-4 h-4" />
+              やり直す
+            </Button>
+            <Button
+              variant="danger"
+              disabled={entries.length === 0}
+              onClick={() => setEmptyConfirmOpen(true)}
+            >
+              <Trash2 className="w-4 h-4" />
+              空にする
+            </Button>
+          </div>
+        }
+      />
+
+      {entries.length === 0 ? (
+        <EmptyState
+          icon={<Trash2 className="w-16 h-16" />}
+          title="ゴミ箱は空です"
+          description="削除したショートカットやグループがここに表示されます"
+        />
+      ) : (
+        <div className="space-y-2">
+          {entries.map((entry) => (
+            <TrashItem
+              key={entry.id}
+              entry={entry}
+              onRestore={() => onRestore(entry.id)}
+              onPurge={() => handlePurge(entry.id)}
+            />
+          ))}
+        </div>
+      )}
+
+      <ConfirmDialog
+        isOpen={emptyConfirmOpen}
+        onClose={() => setEmptyConfirmOpen(false)}
+        onConfirm={() => handlePurge()}
+        title="ゴミ箱を空にする"
+        message="ゴミ箱の項目をすべて完全に削除します。この操作は取り消せません。"
+        confirmText="空にする"
+        cancelText="キャンセル"
+        variant="danger"
+      />
+    </div>
+  );
+}
+
+// ========================================
+// Trash Item Component
+// ========================================
+
+interface TrashItemProps {
+  entry: TrashEntry;
+  onRestore: () => void;
+  onPurge: () => void;
+}
+
+function TrashItem({ entry, onRestore, onPurge }: TrashItemProps) {
+  const { name, icon } =
+    entry.kind === "shortcut" ? entry.shortcut : entry.group;
+
+  return (
+    <Card hover={false}>
+      <div className="flex items-center gap-4 p-4">
+        <div className="shrink-0 w-10 h-10 flex items-center justify-center bg-white/10 rounded-lg">
+          <IconDisplay iconKey={icon} size={20} />
+        </div>
+
+        <div className="flex-1 min-w-0">
+          <h3 className="font-medium text-white truncate">{name}</h3>
+          <p className="text-xs text-gray-500">
+            {entry.kind === "shortcut" ? "ショートカット" : "グループ"} ・{" "}
+            {new Date(entry.deletedAtMs).toLocaleString()} に削除
+          </p>
+        </div>
+
+        <div className="flex items-center gap-1">
+          <Button size="sm" variant="ghost" onClick={onRestore}>
+            <RotateCcw className="w-3.5 h-3.5 mr-1.5" />
+            復元
+          </Button>
+          <button
+            onClick={onPurge}
+            title="完全に削除"
+            className="p-2 rounded-lg hover:bg-red-500/20 text-gray-400 hover:text-red-400 transition-colors"
+          >
+            <Trash2 className="w-4 h-4" />
+          </button>
+        </div>
+      </div>
+    </Card>
+  );
+}
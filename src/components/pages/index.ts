@@ -1,4 +1,6 @@
 export * from "./ShortcutsPage";
 export * from "./GroupsPage";
+export * from "./TrashPage";
+export * from "./ProfilesPage";
 export * from "./SettingsPage";
 export * from "./AboutPage";
@@ -1,13 +1,38 @@
-import { useState, useEffect, useCallback } from "react";
+import { useState, useEffect, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import type {
   Shortcut,
   Group,
   AppData,
   InstalledApp,
   ProcessInfo,
+  RunReport,
+  RunInfo,
+  RunPlan,
+  ValidationReport,
+  QuarantinedFile,
+  BackupInfo,
+  MutationResult,
+  UndoStatus,
+  TrashEntry,
+  ExportSelection,
+  ExportSummary,
+  ImportOptions,
+  ImportReport,
+  ImportResult,
+  SharedShortcut,
+  ShortcutInput,
+  GroupInput,
+  AppDataChangedEvent,
+  DataFormat,
+  DataLocation,
+  ProfileInfo,
 } from "../types";
 
+// 保存が古いリビジョンに基づいていた場合のエラー（src-tauri/src/storage.rs）
+const CONFLICT_ERROR_PREFIX = "revision_conflict";
+
 // ========================================
 // App Data Hook
 // ========================================
@@ -17,134 +42,456 @@ export function useAppData() {
   const [groups, setGroups] = useState<Group[]>([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
+  // data.json を読めなかった場合のエラー（保存エラーとは区別する）
+  const [loadError, setLoadError] = useState<string | null>(null);
+  // 最後に読み込んだ／保存したリビジョン。保存時に競合検出に使う
+  const revisionRef = useRef(0);
+  const [undoStatus, setUndoStatus] = useState<UndoStatus>({
+    undo: null,
+    redo: null,
+  });
+  const [profiles, setProfiles] = useState<ProfileInfo[]>([]);
+
+  const refreshUndoStatus = useCallback(async () => {
+    try {
+      setUndoStatus(await invoke<UndoStatus>("get_undo_status"));
+    } catch (err) {
+      console.error("Failed to get undo status:", err);
+    }
+  }, []);
 
   const loadData = useCallback(async () => {
     try {
       setLoading(true);
       const data = await invoke<AppData>("get_app_data");
+      revisionRef.current = data.revision;
       setShortcuts(data.shortcuts);
       setGroups(data.groups);
       setError(null);
+      setLoadError(null);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      const message = err instanceof Error ? err.message : String(err);
+      setError(message);
+      setLoadError(message);
     } finally {
       setLoading(false);
     }
   }, []);
 
-  useEffect(() => {
-    loadData();
-  }, [loadData]);
-
-  const saveShortcuts = useCallback(async (newShortcuts: Shortcut[]) => {
+  const refreshProfiles = useCallback(async () => {
     try {
-      await invoke("save_shortcuts", { shortcuts: newShortcuts });
-      setShortcuts(newShortcuts);
+      setProfiles(await invoke<ProfileInfo[]>("list_profiles"));
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      console.error("Failed to list profiles:", err);
     }
   }, []);
 
-  const saveGroups = useCallback(async (newGroups: Group[]) => {
-    try {
-      await invoke("save_groups", { groups: newGroups });
-      setGroups(newGroups);
-    } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
-    }
+  useEffect(() => {
+    loadData();
+    refreshUndoStatus();
+    refreshProfiles();
+  }, [loadData, refreshUndoStatus, refreshProfiles]);
+
+  // data.json が外部（手動編集・同期ツール・CLI）で変更されたら反映する
+  useEffect(() => {
+    const unlisten = listen<AppDataChangedEvent>("app-data-changed", (event) => {
+      const payload = event.payload;
+      if (payload.kind === "reloaded") {
+        revisionRef.current = payload.data.revision;
+        setShortcuts(payload.data.shortcuts);
+        setGroups(payload.data.groups);
+        setLoadError(null);
+      } else {
+        setError(payload.error);
+        setLoadError(payload.error);
+      }
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
   }, []);
 
+  const handleSaveError = useCallback(
+    async (err: unknown) => {
+      const message = err instanceof Error ? err.message : String(err);
+      if (message.startsWith(CONFLICT_ERROR_PREFIX)) {
+        // 別のウィンドウや CLI が先に保存した: 上書きせずに最新を読み直す
+        await loadData();
+        setError(
+          "他のウィンドウで変更されたため、最新のデータを読み込み直しました。もう一度操作してください。",
+        );
+      } else {
+        setError(message);
+      }
+    },
+    [loadData],
+  );
+
+  // 保存は1件ずつ順番に行う。並べ替え中のように連続で呼ばれても、
+  // 各保存が直前の保存後のリビジョンを使うようにするため
+  const queueRef = useRef<Promise<unknown>>(Promise.resolve());
+
+  const enqueue = useCallback(
+    <T extends MutationResult>(run: () => Promise<T>) => {
+      const next = queueRef.current.then(async () => {
+        try {
+          const result = await run();
+          revisionRef.current = result.data.revision;
+          setShortcuts(result.data.shortcuts);
+          setGroups(result.data.groups);
+          setUndoStatus(result.undo);
+          return result;
+        } catch (err) {
+          await handleSaveError(err);
+          await refreshUndoStatus();
+          return null;
+        }
+      });
+      queueRef.current = next;
+      return next;
+    },
+    [handleSaveError, refreshUndoStatus],
+  );
+
+  const mutate = useCallback(
+    (command: string, args: Record<string, unknown>) =>
+      enqueue(() =>
+        invoke<MutationResult>(command, {
+          ...args,
+          expectedRevision: revisionRef.current,
+        }),
+      ),
+    [enqueue],
+  );
+
   const addShortcut = useCallback(
-    async (shortcut: Shortcut) => {
-      const newShortcuts = [...shortcuts, shortcut];
-      await saveShortcuts(newShortcuts);
+    async (shortcut: ShortcutInput) => {
+      const result = await mutate("create_shortcut", { shortcut });
+      return result?.id;
     },
-    [shortcuts, saveShortcuts],
+    [mutate],
   );
 
   const updateShortcut = useCallback(
-    async (shortcut: Shortcut) => {
-      const newShortcuts = shortcuts.map((s) =>
-        s.id === shortcut.id ? shortcut : s,
-      );
-      await saveShortcuts(newShortcuts);
+    async (shortcut: ShortcutInput & { id: string }) => {
+      await mutate("update_shortcut", { id: shortcut.id, shortcut });
     },
-    [shortcuts, saveShortcuts],
+    [mutate],
   );
 
   const deleteShortcut = useCallback(
     async (id: string) => {
-      const newShortcuts = shortcuts.filter((s) => s.id !== id);
-      await saveShortcuts(newShortcuts);
+      await mutate("delete_shortcut", { id });
     },
-    [shortcuts, saveShortcuts],
+    [mutate],
   );
 
-  const updateShortcuts = useCallback(
-    async (updatedShortcuts: Shortcut[]) => {
-      const newShortcuts = shortcuts.map((s) => {
-        const updated = updatedShortcuts.find((u) => u.id === s.id);
-        return updated || s;
-      });
-      await saveShortcuts(newShortcuts);
+  // index 番目へ移動する（別グループへの移動も可）
+  const moveShortcut = useCallback(
+    async (id: string, groupId: string, index: number) => {
+      await mutate("move_shortcut", { id, groupId, index });
     },
-    [shortcuts, saveShortcuts],
+    [mutate],
+  );
+
+  const reorderShortcuts = useCallback(
+    async (groupId: string, ids: string[]) => {
+      // 保存を待たずに表示へ反映する（ドラッグ中に元の位置へ戻らないように）
+      setShortcuts((current) =>
+        current.map((s) =>
+          s.groupId === groupId && ids.includes(s.id)
+            ? { ...s, order: ids.indexOf(s.id) }
+            : s,
+        ),
+      );
+      await mutate("reorder_shortcuts", { groupId, ids });
+    },
+    [mutate],
   );
 
   const addGroup = useCallback(
-    async (group: Group) => {
-      const newGroups = [...groups, group];
-      await saveGroups(newGroups);
+    async (group: GroupInput) => {
+      const result = await mutate("create_group", { group });
+      return result?.id;
     },
-    [groups, saveGroups],
+    [mutate],
   );
 
   const updateGroup = useCallback(
-    async (group: Group) => {
-      const newGroups = groups.map((g) => (g.id === group.id ? group : g));
-      await saveGroups(newGroups);
+    async (group: GroupInput & { id: string }) => {
+      await mutate("update_group", { id: group.id, group });
     },
-    [groups, saveGroups],
+    [mutate],
   );
 
+  // 所属していたショートカットはバックエンドでデフォルトグループへ移される
   const deleteGroup = useCallback(
     async (id: string) => {
-      // Move shortcuts from deleted group to default
-      const updatedShortcuts = shortcuts.map((s) =>
-        s.groupId === id ? { ...s, groupId: "default" } : s,
+      await mutate("delete_group", { id });
+    },
+    [mutate],
+  );
+
+  const reorderGroups = useCallback(
+    async (ids: string[]) => {
+      setGroups((current) =>
+        current.map((g) =>
+          ids.includes(g.id) ? { ...g, order: ids.indexOf(g.id) } : g,
+        ),
       );
-      const newGroups = groups.filter((g) => g.id !== id);
+      await mutate("reorder_groups", { ids });
+    },
+    [mutate],
+  );
+
+  // ========================================
+  // Undo / Redo and Trash
+  // ========================================
+
+  const undo = useCallback(async () => {
+    await enqueue(() => invoke<MutationResult>("undo_change"));
+  }, [enqueue]);
 
-      await saveShortcuts(updatedShortcuts);
-      await saveGroups(newGroups);
+  const redo = useCallback(async () => {
+    await enqueue(() => invoke<MutationResult>("redo_change"));
+  }, [enqueue]);
+
+  const listTrash = useCallback(() => invoke<TrashEntry[]>("list_trash"), []);
+
+  const restoreFromTrash = useCallback(
+    async (entryId: string) => {
+      const result = await mutate("restore_from_trash", { entryId });
+      return result?.id;
     },
-    [shortcuts, groups, saveShortcuts, saveGroups],
+    [mutate],
+  );
+
+  // entryId を省略するとゴミ箱を空にする
+  const purgeTrash = useCallback(
+    (entryId?: string) => invoke<number>("purge_trash", { entryId }),
+    [],
+  );
+
+  // ========================================
+  // Bundle Export / Import
+  // ========================================
+
+  const exportBundle = useCallback(
+    (path: string, selection?: ExportSelection) =>
+      invoke<ExportSummary>("export_bundle", { path, selection }),
+    [],
+  );
+
+  // 保存せずに、取り込んだ場合の結果だけを返す
+  const previewImport = useCallback(
+    (path: string, options: ImportOptions) =>
+      invoke<ImportReport>("preview_bundle_import", { path, options }),
+    [],
+  );
+
+  const importBundle = useCallback(
+    async (path: string, options: ImportOptions) => {
+      const result = await enqueue(() =>
+        invoke<ImportResult>("import_bundle", {
+          path,
+          options,
+          expectedRevision: revisionRef.current,
+        }),
+      );
+      return result?.report;
+    },
+    [enqueue],
+  );
+
+  // ========================================
+  // Share Codes
+  // ========================================
+
+  const createShareCode = useCallback(
+    (shortcutId: string) => invoke<string>("create_share_code", { shortcutId }),
+    [],
+  );
+
+  // 保存せずに、共有コードの中身だけを返す（壊れたコードはエラー）
+  const previewShareCode = useCallback(
+    (code: string) => invoke<SharedShortcut>("preview_share_code", { code }),
+    [],
+  );
+
+  const importShareCode = useCallback(
+    async (code: string, groupId?: string) => {
+      const result = await mutate("import_share_code", { code, groupId });
+      return result?.id;
+    },
+    [mutate],
   );
 
   const executeShortcut = useCallback(async (shortcut: Shortcut) => {
     try {
-      const results = await invoke<string[]>("execute_shortcut", { shortcut });
-      return results;
+      const report = await invoke<RunReport>("execute_shortcut", { shortcut });
+      return report;
     } catch (err) {
       throw new Error(err instanceof Error ? err.message : String(err));
     }
   }, []);
 
+  // ========================================
+  // Corrupt Data Recovery
+  // ========================================
+
+  const listQuarantined = useCallback(
+    () => invoke<QuarantinedFile[]>("list_quarantined_data"),
+    [],
+  );
+
+  const restoreQuarantined = useCallback(
+    async (fileName: string) => {
+      await invoke<AppData>("restore_quarantined_data", { fileName });
+      await loadData();
+    },
+    [loadData],
+  );
+
+  const resetData = useCallback(async () => {
+    await invoke<AppData>("reset_app_data");
+    await loadData();
+  }, [loadData]);
+
+  // ========================================
+  // Backups
+  // ========================================
+
+  const listBackups = useCallback(
+    () => invoke<BackupInfo[]>("list_data_backups"),
+    [],
+  );
+
+  const restoreBackup = useCallback(
+    async (fileName: string) => {
+      await invoke<AppData>("restore_data_backup", { fileName });
+      await loadData();
+    },
+    [loadData],
+  );
+
+  // ========================================
+  // Profiles
+  // ========================================
+
+  const createProfile = useCallback(async (name: string) => {
+    setProfiles(await invoke<ProfileInfo[]>("create_profile", { name }));
+  }, []);
+
+  const duplicateProfile = useCallback(async (source: string, name: string) => {
+    setProfiles(
+      await invoke<ProfileInfo[]>("duplicate_profile", { source, name }),
+    );
+  }, []);
+
+  const deleteProfile = useCallback(async (name: string) => {
+    setProfiles(await invoke<ProfileInfo[]>("delete_profile", { name }));
+  }, []);
+
+  // 切り替え先のデータが読めなくても切り替えは済んでいるので、必ず読み直す
+  // （壊れていれば復旧画面になる）
+  const switchProfile = useCallback(
+    async (name: string) => {
+      try {
+        await invoke<AppData>("switch_profile", { name });
+      } finally {
+        await Promise.all([loadData(), refreshUndoStatus(), refreshProfiles()]);
+      }
+    },
+    [loadData, refreshUndoStatus, refreshProfiles],
+  );
+
+  const getDataLocation = useCallback(
+    () => invoke<DataLocation>("get_data_location"),
+    [],
+  );
+
+  const getDataFormat = useCallback(
+    () => invoke<DataFormat>("get_data_format"),
+    [],
+  );
+
+  // データファイルを指定した書式で書き直す（書き直したファイルのパスを返す）
+  const setDataFormat = useCallback(
+    async (format: DataFormat) => {
+      const path = await invoke<string>("set_data_format", { format });
+      await loadData();
+      return path;
+    },
+    [loadData],
+  );
+
+  // 保存せずに検証だけ行う（data 省略時は保存済みデータ）
+  const validateAppData = useCallback(
+    (data?: AppData) => invoke<ValidationReport>("validate_app_data", { data }),
+    [],
+  );
+
+  // 実行せずに、実行した場合の内容だけを取得する
+  const planShortcut = useCallback(
+    (shortcut: Shortcut) => invoke<RunPlan>("plan_shortcut", { shortcut }),
+    [],
+  );
+
+  const cancelShortcut = useCallback(async (shortcutId: string) => {
+    const runs = await invoke<RunInfo[]>("get_active_runs");
+    await Promise.all(
+      runs
+        .filter((run) => run.shortcutId === shortcutId)
+        .map((run) => invoke("cancel_shortcut_run", { runId: run.runId })),
+    );
+  }, []);
+
   return {
     shortcuts,
     groups,
     loading,
     error,
+    loadError,
     loadData,
+    listQuarantined,
+    restoreQuarantined,
+    resetData,
+    listBackups,
+    restoreBackup,
+    profiles,
+    createProfile,
+    duplicateProfile,
+    deleteProfile,
+    switchProfile,
+    getDataLocation,
+    getDataFormat,
+    setDataFormat,
     addShortcut,
     updateShortcut,
-    updateShortcuts,
     deleteShortcut,
+    moveShortcut,
+    reorderShortcuts,
     addGroup,
     updateGroup,
     deleteGroup,
+    reorderGroups,
+    undoStatus,
+    undo,
+    redo,
+    listTrash,
+    restoreFromTrash,
+    purgeTrash,
+    exportBundle,
+    previewImport,
+    importBundle,
+    createShareCode,
+    previewShareCode,
+    importShareCode,
     executeShortcut,
-    saveGroups,
+    planShortcut,
+    cancelShortcut,
+    validateAppData,
   };
 }
 
@@ -0,0 +1,56 @@
+import { useState, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { HistoryEntry, HistoryQuery, HistoryRetention } from "../types";
+
+// ========================================
+// Execution History Hook
+// ========================================
+
+export function useExecutionHistory() {
+  const [entries, setEntries] = useState<HistoryEntry[]>([]);
+  const [loading, setLoading] = useState(false);
+
+  const loadHistory = useCallback(async (query?: HistoryQuery) => {
+    try {
+      setLoading(true);
+      const list = await invoke<HistoryEntry[]>("get_execution_history", {
+        query,
+      });
+      setEntries(list);
+    } catch (err) {
+      console.error("Failed to load execution history:", err);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  const pruneHistory = useCallback(
+    async (request?: { beforeMs?: number; shortcutId?: string }) => {
+      const removed = await invoke<number>("prune_execution_history", {
+        request,
+      });
+      return removed;
+    },
+    [],
+  );
+
+  const getRetention = useCallback(
+    () => invoke<HistoryRetention>("get_history_retention"),
+    [],
+  );
+
+  const setRetention = useCallback(
+    (retention: HistoryRetention) =>
+      invoke("set_history_retention", { retention }),
+    [],
+  );
+
+  return {
+    entries,
+    loading,
+    loadHistory,
+    pruneHistory,
+    getRetention,
+    setRetention,
+  };
+}
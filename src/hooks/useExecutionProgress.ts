@@ -0,0 +1,101 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { ActionResult, ExecutionEvent, RunStatus } from "../types";
+
+// ========================================
+// Execution Progress Hook
+// ========================================
+
+export interface RunProgress {
+  runId: string;
+  shortcutId: string;
+  shortcutName: string;
+  actionCount: number;
+  currentIndex: number | null;
+  results: ActionResult[];
+  status: RunStatus | null; // null while running
+}
+
+// How long a finished run stays visible
+const FINISHED_RUN_TTL_MS = 4000;
+
+export function useExecutionProgress() {
+  const [runs, setRuns] = useState<Record<string, RunProgress>>({});
+
+  useEffect(() => {
+    const unlisten = listen<ExecutionEvent>("execution-progress", (event) => {
+      const payload = event.payload;
+
+      setRuns((prev) => {
+        const run = prev[payload.runId];
+        switch (payload.kind) {
+          case "run_started":
+            return {
+              ...prev,
+              [payload.runId]: {
+                runId: payload.runId,
+                shortcutId: payload.shortcutId,
+                shortcutName: payload.shortcutName,
+                actionCount: payload.actionCount,
+                currentIndex: null,
+                results: [],
+                status: null,
+              },
+            };
+          case "action_started":
+            if (!run) return prev;
+            return {
+              ...prev,
+              [payload.runId]: { ...run, currentIndex: payload.index },
+            };
+          case "action_finished":
+            if (!run) return prev;
+            return {
+              ...prev,
+              [payload.runId]: {
+                ...run,
+                currentIndex: null,
+                results: [...run.results, payload.result],
+              },
+            };
+          case "run_finished":
+            if (!run) return prev;
+            return {
+              ...prev,
+              [payload.runId]: {
+                ...run,
+                currentIndex: null,
+                results: payload.report.results,
+                status: payload.report.status,
+              },
+            };
+        }
+      });
+
+      if (payload.kind === "run_finished") {
+        setTimeout(() => {
+          setRuns((prev) => {
+            const next = { ...prev };
+            delete next[payload.runId];
+            return next;
+          });
+        }, FINISHED_RUN_TTL_MS);
+      }
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const cancelRun = useCallback(async (runId: string) => {
+    try {
+      await invoke("cancel_shortcut_run", { runId });
+    } catch (err) {
+      console.error("Failed to cancel run:", err);
+    }
+  }, []);
+
+  return { runs: Object.values(runs), cancelRun };
+}
@@ -9,7 +9,18 @@ export interface WindowConfig {
   height?: number;
 }
 
-export type Action =
+export type ErrorPolicy =
+  | { mode: "abort" }
+  | { mode: "continue" }
+  | { mode: "retry"; retries: number; delayMs: number; backoff?: number };
+
+// Per-action options stored next to the action's `type` tag
+export interface ActionOptions {
+  onError?: ErrorPolicy;
+  timeoutMs?: number;
+}
+
+export type Action = (
   | {
       type: "launch";
       path: string;
@@ -19,7 +30,9 @@ export type Action =
   | { type: "kill"; processName: string }
   | { type: "open_folder"; path: string; windowConfig?: WindowConfig }
   | { type: "open_url"; url: string; windowConfig?: WindowConfig }
-  | { type: "delay"; ms: number };
+  | { type: "delay"; ms: number }
+) &
+  ActionOptions;
 
 export interface Shortcut {
   id: string;
@@ -27,6 +40,9 @@ export interface Shortcut {
   icon: string;
   groupId: string;
   actions: Action[];
+  onError?: ErrorPolicy;
+  // advanced-shortcut://run/... のリンクから確認なしで実行してよいか
+  linkRunnable?: boolean;
   order: number;
   createdAt: string;
   updatedAt: string;
@@ -42,10 +58,149 @@ export interface Group {
 }
 
 export interface AppData {
+  revision: number; // incremented on every save
   shortcuts: Shortcut[];
   groups: Group[];
 }
 
+export interface SaveResult {
+  revision: number;
+  validation: ValidationReport;
+}
+
+// Fields sent when creating or updating; id, order and timestamps are set by the backend
+export type ShortcutInput = Pick<
+  Shortcut,
+  "name" | "icon" | "groupId" | "actions" | "onError" | "linkRunnable"
+>;
+
+export type GroupInput = Pick<Group, "name" | "color" | "icon" | "isExpanded">;
+
+// Result of the single-item create/update/delete/move commands and undo/redo
+export interface MutationResult {
+  data: AppData;
+  id?: string; // set when an item was created or restored
+  validation: ValidationReport;
+  undo: UndoStatus;
+}
+
+export type ChangeKind =
+  | "create_shortcut"
+  | "update_shortcut"
+  | "delete_shortcut"
+  | "move_shortcut"
+  | "reorder_shortcuts"
+  | "create_group"
+  | "update_group"
+  | "delete_group"
+  | "move_group"
+  | "reorder_groups"
+  | "restore_from_trash"
+  | "import_bundle"
+  | "save";
+
+// What the next undo / redo would revert or reapply (null when unavailable)
+export interface UndoStatus {
+  undo: ChangeKind | null;
+  redo: ChangeKind | null;
+}
+
+// A deleted shortcut or group that can be restored
+export type TrashEntry = { id: string; deletedAtMs: number } & (
+  | { kind: "shortcut"; shortcut: Shortcut }
+  | { kind: "group"; group: Group; shortcutIds: string[] }
+);
+
+// ========================================
+// Bundle Export / Import
+// ========================================
+
+// Exports everything when both lists are empty
+export interface ExportSelection {
+  shortcutIds?: string[];
+  groupIds?: string[];
+}
+
+export interface ExportSummary {
+  path: string;
+  shortcutCount: number;
+  groupCount: number;
+}
+
+export interface ImportOptions {
+  groups?: "merge" | "replace"; // for groups that already exist by name
+  onConflict?: "skip" | "rename" | "replace"; // for shortcuts that already exist by name
+}
+
+export interface ImportConflict {
+  kind: "group" | "shortcut";
+  name: string;
+  groupName: string | null;
+  resolution: "merged" | "replaced" | "skipped" | "renamed";
+  newName: string | null;
+}
+
+export interface ImportReport {
+  createdGroups: number;
+  createdShortcuts: number;
+  replacedShortcuts: number;
+  skippedShortcuts: number;
+  removedShortcuts: number;
+  conflicts: ImportConflict[];
+}
+
+export interface ImportResult extends MutationResult {
+  report: ImportReport;
+}
+
+// Where the data folder is and which setting chose it (src-tauri/src/data_dir.rs)
+export interface DataLocation {
+  path: string;
+  source: "cli" | "env" | "portable" | "default";
+}
+
+// A named set of shortcuts and groups (src-tauri/src/profiles.rs)
+export interface ProfileInfo {
+  name: string;
+  active: boolean;
+  path: string;
+}
+
+// Format of the data file and of bundles, chosen by file extension
+export type DataFormat = "json" | "yaml" | "toml";
+
+// The contents of a share code (src-tauri/src/share_code.rs)
+export interface SharedShortcut {
+  version: number;
+  name: string;
+  icon: string;
+  actions: Action[];
+  onError: ErrorPolicy;
+}
+
+// Emitted when data.json is changed outside the app
+export type AppDataChangedEvent =
+  | { kind: "reloaded"; data: AppData; validation: ValidationReport }
+  | { kind: "failed"; error: string };
+
+// A copy of an unreadable data.json, kept so it can be repaired and restored
+export interface QuarantinedFile {
+  fileName: string;
+  path: string;
+  size: number;
+  modifiedMs: number;
+}
+
+// An automatic copy of data.json taken before each save
+export interface BackupInfo {
+  fileName: string;
+  path: string;
+  size: number;
+  createdMs: number;
+  shortcutCount?: number | null; // null if the backup cannot be parsed
+  groupCount?: number | null;
+}
+
 export interface ProcessInfo {
   pid: number;
   name: string;
@@ -69,6 +224,144 @@ export interface InstalledApp {
   icon?: string;
 }
 
+// ========================================
+// Execution Types
+// ========================================
+
+export type ActionError =
+  | { kind: "not_found"; target: string }
+  | { kind: "spawn_failed"; target: string; message: string }
+  | { kind: "open_failed"; target: string; message: string }
+  | { kind: "timeout"; timeoutMs: number }
+  | { kind: "cancelled" }
+  | { kind: "window_not_found"; target: string }
+  | { kind: "permission_denied"; target: string; message: string };
+
+export type ActionStatus = "succeeded" | "failed" | "skipped";
+
+export interface ActionResult {
+  index: number;
+  actionType: Action["type"];
+  status: ActionStatus;
+  durationMs: number;
+  attempts: number;
+  pids: number[];
+  hwnds: number[];
+  // ウィンドウ設定がある場合のみ。見つからなくても失敗にはしない
+  windowsFound?: boolean | null;
+  warnings: string[];
+  message: string;
+  error?: ActionError | null;
+}
+
+export type RunStatus = "succeeded" | "failed" | "aborted" | "cancelled";
+
+export interface RunReport {
+  runId: string;
+  shortcutId: string;
+  status: RunStatus;
+  startedAtMs: number;
+  durationMs: number;
+  results: ActionResult[];
+}
+
+export interface RunInfo {
+  runId: string;
+  shortcutId: string;
+  startedAtMs: number;
+}
+
+export type ExecutionEvent =
+  | {
+      kind: "run_started";
+      runId: string;
+      shortcutId: string;
+      shortcutName: string;
+      actionCount: number;
+    }
+  | { kind: "action_started"; runId: string; index: number; actionType: string }
+  | { kind: "action_finished"; runId: string; result: ActionResult }
+  | { kind: "run_finished"; runId: string; report: RunReport };
+
+export interface PlannedAction {
+  index: number;
+  actionType: Action["type"];
+  summary: string;
+  resolved: Action; // environment variables expanded, for display only
+  onError: ErrorPolicy;
+  timeoutMs?: number | null;
+  pids: number[];
+  warnings: string[];
+  error?: ActionError | null; // expected failure
+}
+
+export interface RunPlan {
+  shortcutId: string;
+  shortcutName: string;
+  ok: boolean;
+  delayMs: number;
+  actions: PlannedAction[];
+}
+
+// ========================================
+// Validation Types
+// ========================================
+
+export type ValidationSeverity = "warning" | "error";
+
+export type ValidationIssueCode =
+  | "duplicate_shortcut_id"
+  | "duplicate_group_id"
+  | "dangling_group"
+  | "empty_target"
+  | "invalid_url"
+  | "missing_launch_target"
+  | "delay_too_long";
+
+export interface ValidationIssue {
+  severity: ValidationSeverity;
+  code: ValidationIssueCode;
+  message: string;
+  shortcutId?: string | null;
+  groupId?: string | null;
+  actionIndex?: number | null;
+}
+
+export interface ValidationReport {
+  issues: ValidationIssue[];
+}
+
+// ========================================
+// Execution History Types
+// ========================================
+
+export type RunSource = "gui" | "cli" | "trigger";
+
+export interface HistoryEntry {
+  runId: string;
+  shortcutId: string;
+  shortcutName: string;
+  source: RunSource;
+  status: RunStatus;
+  startedAtMs: number;
+  durationMs: number;
+  results: ActionResult[];
+}
+
+export interface HistoryQuery {
+  shortcutId?: string;
+  source?: RunSource;
+  status?: RunStatus;
+  sinceMs?: number;
+  limit?: number;
+  offset?: number;
+}
+
+export interface HistoryRetention {
+  maxEntries: number;
+  maxAgeDays: number; // 0 = keep forever
+}
+
 // ========================================
 // UI Types
 // ========================================